@@ -0,0 +1,21 @@
+// Regenerates the C header for the "ffi" feature, see src/ffi.rs
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir: String = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config: cbindgen::Config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+
+    match cbindgen::generate_with_config(&crate_dir, config) {
+        Ok(bindings) => {
+            bindings.write_to_file(format!("{}/include/rasan_chess.h", crate_dir));
+        },
+        Err(error) => panic!("Could not generate C header: {}", error)
+    }
+}
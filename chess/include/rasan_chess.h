@@ -0,0 +1,224 @@
+#ifndef RASAN_CHESS_H
+#define RASAN_CHESS_H
+
+/* Generated from src/ffi.rs by cbindgen. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Number of Chess960 start positions.
+ */
+#define CHESS960_POSITIONS 960
+
+/**
+ * Deepest depth a search goes to when nothing else stops it.
+ */
+#define MAX_DEPTH 64
+
+/**
+ * Score of a king captured right now, captures further ahead score one less per ply.
+ */
+#define WIN_SCORE 30000
+
+typedef enum ChessColor {
+  CHESS_COLOR_WHITE = 0,
+  CHESS_COLOR_BLACK,
+} ChessColor;
+
+typedef enum ChessGameState {
+  CHESS_GAME_STATE_IN_PROGRESS = 0,
+  CHESS_GAME_STATE_PROMOTION,
+  CHESS_GAME_STATE_CHECK,
+  CHESS_GAME_STATE_GAME_OVER,
+  CHESS_GAME_STATE_DUCK_PLACEMENT,
+} ChessGameState;
+
+/**
+ * Roles in the order of `ROLES`.
+ */
+typedef enum ChessRole {
+  CHESS_ROLE_KING = 0,
+  CHESS_ROLE_QUEEN,
+  CHESS_ROLE_ROOK,
+  CHESS_ROLE_BISHOP,
+  CHESS_ROLE_KNIGHT,
+  CHESS_ROLE_PAWN,
+  CHESS_ROLE_ARCHBISHOP,
+  CHESS_ROLE_CHANCELLOR,
+  CHESS_ROLE_AMAZON,
+  CHESS_ROLE_NIGHTRIDER,
+  CHESS_ROLE_GRASSHOPPER,
+} ChessRole;
+
+/**
+ * Result code of every fallible call.
+ */
+typedef enum ChessStatus {
+  CHESS_STATUS_OK = 0,
+  CHESS_STATUS_NULL_POINTER,
+  CHESS_STATUS_INVALID_STRING,
+  CHESS_STATUS_INVALID_POSITION,
+  CHESS_STATUS_NO_PIECE,
+  CHESS_STATUS_NOT_YOUR_TURN,
+  CHESS_STATUS_ILLEGAL_MOVE,
+  CHESS_STATUS_GAME_OVER,
+  CHESS_STATUS_PROMOTION_PENDING,
+  CHESS_STATUS_PROMOTION_NOT_ALLOWED,
+  CHESS_STATUS_INVALID_FEN,
+  CHESS_STATUS_BUFFER_TOO_SMALL,
+  CHESS_STATUS_DUCK_PENDING,
+  CHESS_STATUS_SUICIDAL_MOVE,
+  CHESS_STATUS_INVALID_ARGUMENT,
+} ChessStatus;
+
+/**
+ * Number of files and ranks of a board.
+ */
+typedef struct Dimensions Dimensions;
+
+typedef struct Game Game;
+
+/**
+ * A square, column 1 is the a-file and row 1 is white's back rank.
+ */
+typedef struct ChessSquare {
+  int8_t column;
+  int8_t row;
+} ChessSquare;
+
+
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Start a game from the initial position. Free it with `chess_game_free`.
+ */
+struct Game *chess_game_new(void);
+
+/**
+ * Set up a game from FEN and store the new handle in `out`.
+ *
+ * # Safety
+ * `fen` must be a NUL terminated string and `out` a valid pointer.
+ */
+enum ChessStatus chess_game_from_fen(const char *fen, struct Game **out);
+
+/**
+ * Release a game. Passing NULL does nothing.
+ *
+ * # Safety
+ * `game` must come from this library and not be used afterwards.
+ */
+void chess_game_free(struct Game *game);
+
+/**
+ * Move a piece, e.g. from "e2" to "e4".
+ *
+ * # Safety
+ * `game` must be a live handle, `from` and `to` NUL terminated strings.
+ */
+enum ChessStatus chess_make_move(struct Game *game, const char *from, const char *to);
+
+/**
+ * Promote the pawn on `square` after it reached the last rank. `role` is a `ChessRole`.
+ *
+ * # Safety
+ * `game` must be a live handle and `square` a NUL terminated string.
+ */
+enum ChessStatus chess_set_promotion(struct Game *game, const char *square, uint32_t role);
+
+/**
+ * Store the current game state in `out`.
+ *
+ * # Safety
+ * `game` must be a live handle and `out` a valid pointer.
+ */
+enum ChessStatus chess_game_state(const struct Game *game, enum ChessGameState *out);
+
+/**
+ * Store how many times `color`, a `ChessColor`, has given check in `out`, counted in Three-check.
+ *
+ * # Safety
+ * `game` must be a live handle and `out` a valid pointer.
+ */
+enum ChessStatus chess_checks(const struct Game *game, uint32_t color, uint32_t *out);
+
+/**
+ * Store the color whose turn it is in `out`.
+ *
+ * # Safety
+ * `game` must be a live handle and `out` a valid pointer.
+ */
+enum ChessStatus chess_active_color(const struct Game *game, enum ChessColor *out);
+
+/**
+ * Write the squares the piece on `square` can move to into `buffer`.
+ *
+ * `count` is always set to the number of moves. If it exceeds `capacity`
+ * nothing is written and CHESS_STATUS_BUFFER_TOO_SMALL is returned, so
+ * the call can be repeated with a larger buffer. A piece never has more
+ * moves than the board has squares, at most 100 in Grand chess.
+ *
+ * # Safety
+ * `game` must be a live handle, `square` a NUL terminated string, `count`
+ * a valid pointer and `buffer` must hold `capacity` squares.
+ */
+enum ChessStatus chess_legal_moves(const struct Game *game,
+                                   const char *square,
+                                   struct ChessSquare *buffer,
+                                   size_t capacity,
+                                   size_t *count);
+
+/**
+ * Put the duck on `square` after a move in Duck chess. The opponent moves next.
+ *
+ * # Safety
+ * `game` must be a live handle and `square` a NUL terminated string.
+ */
+enum ChessStatus chess_place_duck(struct Game *game, const char *square);
+
+/**
+ * Write the squares the duck can be put on into `buffer`, none unless the
+ * game state is CHESS_GAME_STATE_DUCK_PLACEMENT. `count` and the status
+ * work as in `chess_legal_moves`.
+ *
+ * # Safety
+ * `game` must be a live handle, `count` a valid pointer and `buffer` must
+ * hold `capacity` squares.
+ */
+enum ChessStatus chess_duck_squares(const struct Game *game,
+                                    struct ChessSquare *buffer,
+                                    size_t capacity,
+                                    size_t *count);
+
+/**
+ * Store the number of bytes the FEN of the position takes in `out`, with
+ * the terminating NUL. Pockets, check counters and wide boards make it
+ * longer than on a standard board.
+ *
+ * # Safety
+ * `game` must be a live handle and `out` a valid pointer.
+ */
+enum ChessStatus chess_fen_length(const struct Game *game, size_t *out);
+
+/**
+ * Write the position in FEN as a NUL terminated string into `buffer`. If it
+ * does not fit, nothing is written and CHESS_STATUS_BUFFER_TOO_SMALL is
+ * returned, `chess_fen_length` tells how large the buffer has to be.
+ *
+ * # Safety
+ * `game` must be a live handle and `buffer` must hold `capacity` bytes.
+ */
+enum ChessStatus chess_to_fen(const struct Game *game, char *buffer, size_t capacity);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* RASAN_CHESS_H */
@@ -0,0 +1,89 @@
+use crate::{Color, Game, GameState, MoveError, Position, Role};
+
+/* King-danger assist.
+ *
+ * In Chess royale a king may walk into capture, and moves which leave it
+ * hanging stay legal. The assist only warns: `get_annotated_moves` tells
+ * for each move whether the opponent could capture the king right after
+ * it, and with `set_king_danger_assist` turned on such moves are refused
+ * with `MoveError::SuicidalMove` until they are made again confirmed.
+ *
+ *   game.set_king_danger_assist(true);
+ *   match game.try_make_move("g2", "g4") {
+ *       Err(MoveError::SuicidalMove) => game.try_make_move_confirmed("g2", "g4", true),
+ *       other => other
+ *   };
+ *
+ * The move is looked at as it leaves the board. A pawn reaching the last
+ * rank counts as a queen, and the duck placed after a move in Duck chess
+ * is not taken into account.
+ */
+
+impl Game {
+
+    /// Turn the king-danger assist on or off.
+    pub fn set_king_danger_assist(&mut self, on: bool) {
+        self.king_danger_assist = on;
+    }
+
+    /// Whether the king-danger assist is on.
+    pub fn has_king_danger_assist(&self) -> bool {
+        return self.king_danger_assist;
+    }
+
+    /// If a piece of the active color stands on the given tile, return its possible new
+    /// positions, each with whether the move leaves the own king capturable next turn.
+    /// Returns None for an empty tile or a piece of the opponent.
+    pub fn get_annotated_moves(&self, _position: String) -> Option<Vec<(Position, bool)>> {
+        let from: Position = Position::parse(&_position)?;
+        match self.get_piece_at(&from) {
+            Some((color, _)) if color == self.active_color => (),
+            _ => return None
+        }
+        let moves: Vec<Position> = self.get_possible_moves(_position)?;
+
+        return Some(moves.into_iter().map(|to| {
+            let danger: bool = self.endangers_king(&from, &to);
+            return (to, danger);
+        }).collect());
+    }
+
+    /// Same as `make_move`, with the king-danger assist on a move leaving the own
+    /// king capturable is only made when `confirm` is set.
+    pub fn make_move_confirmed(&mut self, _from: String, _to: String, confirm: bool) -> Option<GameState> {
+        match self.try_make_move_confirmed(&_from, &_to, confirm) {
+            Ok(state) => return Some(state),
+            Err(error) => {
+                println!("{}", error);
+                return Some(self.state);
+            }
+        }
+    }
+
+    // Whether a king of the mover could be captured right after the move.
+    // Moves which cannot be made, or which win the game, are no danger.
+    pub(crate) fn endangers_king(&self, from: &Position, to: &Position) -> bool {
+        let color: Color = self.active_color.clone();
+
+        // Play the move on a copy without the assist
+        let mut after: Game = self.clone();
+        after.king_danger_assist = false;
+        let result: Result<GameState, MoveError> = after.try_make_move(&from.to_string(), &to.to_string());
+        if result.is_err() {
+            return false;
+        }
+
+        // Look ahead with a queen
+        if after.state == GameState::Promotion {
+            let _ = after.try_set_promotion(&to.to_string(), Role::Queen);
+        }
+
+        if after.state == GameState::GameOver {
+            return after.get_winner() != Some(color);
+        }
+
+        return after.board.values()
+            .filter(|piece| piece.role == Role::King && piece.color == color)
+            .any(|king| after.is_attacked(&king.position, color.opponent()));
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{Color, Dimensions, Game, Piece, Position};
+
+/* Attack maps.
+ *
+ * A square is attacked by a piece which could capture an enemy piece
+ * standing there, whatever stands there now. Pawns attack diagonally
+ * forward and not the squares they move to, and a piece behind another
+ * one attacks nothing past it. Own pieces count too, so attacking a
+ * square with an own piece on it means defending that piece.
+ *
+ * Attacks follow how the pieces move and leave out variant rules such as
+ * kings which never capture in Atomic. The duck in Duck chess blocks
+ * attacks and is never attacked itself.
+ *
+ * `AttackMap` holds one bit per square, the first rank first and files
+ * from left to right, so boards up to 128 squares fit.
+ */
+
+/// Every square one color attacks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttackMap {
+    dimensions: Dimensions,
+    bits: u128
+}
+
+impl Game {
+
+    /// Get the squares of the pieces of a color which attack the given square, ordered by square.
+    pub fn attackers(&self, _square: &Position, _color: Color) -> Vec<Position> {
+        return attackers(&self.board, &self.get_dimensions(), &self.blocked_squares(), _square, &_color);
+    }
+
+    /// Whether a piece of the given color attacks the square.
+    pub fn is_attacked(&self, _square: &Position, by: Color) -> bool {
+        return !self.attackers(_square, by).is_empty();
+    }
+
+    /// Get every square the pieces of a color attack.
+    pub fn attack_map(&self, _color: Color) -> AttackMap {
+        let dimensions: Dimensions = self.get_dimensions();
+        let blocked: Vec<Position> = self.blocked_squares();
+        let mut map: AttackMap = AttackMap { dimensions, bits: 0 };
+
+        for piece in self.board.values().filter(|piece| piece.color == _color) {
+            if let Some(attacks) = piece.get_attacks(&self.board, &dimensions, &blocked) {
+                for square in attacks {
+                    map.bits |= 1 << map.index(&square);
+                }
+            }
+        }
+
+        return map;
+    }
+
+    // Squares no piece can move through or capture on, the duck
+    pub(crate) fn blocked_squares(&self) -> Vec<Position> {
+        return self.duck.iter().cloned().collect();
+    }
+}
+
+impl AttackMap {
+
+    /// Whether the square is attacked.
+    pub fn contains(&self, _square: &Position) -> bool {
+        if !self.dimensions.contains(_square) {
+            return false;
+        }
+        return self.bits & (1 << self.index(_square)) != 0;
+    }
+
+    /// Get every attacked square, ordered by square.
+    pub fn squares(&self) -> Vec<Position> {
+        let mut squares: Vec<Position> = Vec::new();
+
+        for row in 1..=self.dimensions.rows {
+            for column in 1..=self.dimensions.columns {
+                let square: Position = Position { row, column };
+                if self.contains(&square) {
+                    squares.push(square);
+                }
+            }
+        }
+
+        return squares;
+    }
+
+    /// Get the number of attacked squares.
+    pub fn count(&self) -> u32 {
+        return self.bits.count_ones();
+    }
+
+    /// Get the map as bits, a1 is the lowest bit and each rank follows the one below.
+    pub fn bits(&self) -> u128 {
+        return self.bits;
+    }
+
+    // Bit of a square
+    fn index(&self, _square: &Position) -> u32 {
+        return ((_square.row - 1) as u32) * self.dimensions.columns as u32 + (_square.column - 1) as u32;
+    }
+}
+
+/// Output example, attacked squares are "x":
+/// . . . . . . . .
+/// . . . . . . . .
+/// . . . . . . . .
+/// . . . . . . . .
+/// . . . . . . . .
+/// x x x x x x x x
+/// x x x x x x x x
+/// . x x x x x x .
+impl fmt::Display for AttackMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut output: String = String::new();
+
+        for row in (1..=self.dimensions.rows).rev() {
+            let mut squares: Vec<&str> = Vec::new();
+
+            for column in 1..=self.dimensions.columns {
+                squares.push(if self.contains(&Position { row, column }) { "x" } else { "." });
+            }
+
+            output.push_str(&squares.join(" "));
+            output.push('\n');
+        }
+
+        write!(f, "{}", output)
+    }
+}
+
+// Squares of the pieces of a color which attack a square on the given board
+pub(crate) fn attackers(board: &HashMap<Position, Piece>, dimensions: &Dimensions, blocked: &[Position], _square: &Position, _color: &Color) -> Vec<Position> {
+    let mut attackers: Vec<Position> = board.values()
+        .filter(|piece| piece.color == *_color)
+        .filter(|piece| match piece.get_attacks(board, dimensions, blocked) {
+            Some(attacks) => attacks.contains(_square),
+            None => false
+        })
+        .map(|piece| piece.position.clone())
+        .collect();
+
+    attackers.sort();
+    return attackers;
+}
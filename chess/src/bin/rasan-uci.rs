@@ -0,0 +1,18 @@
+use std::io::{self, BufRead};
+
+use rasan_chess::engine::Uci;
+
+// Reads UCI commands from standard input until quit
+fn main() {
+    let mut uci: Uci = Uci::new(Box::new(io::stdout()));
+
+    for line in io::stdin().lock().lines() {
+        let line: String = match line {
+            Ok(line) => line,
+            Err(_) => break
+        };
+        if !uci.handle(&line) {
+            break;
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{Color, Game, GameState, MoveError, Piece, Role, Variant};
+
+/* Bughouse, two games of Crazyhouse played side by side by two teams.
+ *
+ * Team A plays white on board A and black on board B, team B the other
+ * two sides. A piece captured on one board goes into the pocket of the
+ * partner of the capturer, who plays the same color as the piece on the
+ * other board. The boards move independently of each other.
+ *
+ * The first king captured on either board ends the whole match, and the
+ * team that captured it wins. Games are exported as BPGN, where every
+ * move carries its number and board, e.g. "1A. e4 1a. e5 1B. d4".
+ */
+
+/// One of the two boards of a Bughouse match.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BughouseBoard {
+    A,
+    B
+}
+
+/// One of the two teams of a Bughouse match. Team A plays white on
+/// board A and black on board B.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BughouseTeam {
+    A,
+    B
+}
+
+/// Two linked games of Bughouse.
+pub struct Bughouse {
+    board_a: Game,
+    board_b: Game,
+
+    // Boards in the order moves were made on them, for BPGN
+    moves: Vec<BughouseBoard>
+}
+
+impl Bughouse {
+
+    /// Start a match with both boards in the initial position.
+    pub fn new() -> Bughouse {
+        return Bughouse {
+            board_a: Game::new_variant(Variant::Bughouse),
+            board_b: Game::new_variant(Variant::Bughouse),
+            moves: Vec::new()
+        };
+    }
+
+    /// Get the game on one of the boards.
+    pub fn get_board(&self, _board: BughouseBoard) -> &Game {
+        match _board {
+            BughouseBoard::A => return &self.board_a,
+            BughouseBoard::B => return &self.board_b
+        }
+    }
+
+    /// Move a piece on one of the boards and return the resulting state of
+    /// that board. A captured piece goes to the partner on the other board.
+    pub fn try_make_move(&mut self, _board: BughouseBoard, _from: &str, _to: &str) -> Result<GameState, MoveError> {
+        let game: &Game = self.get_board(_board);
+
+        // Look at the target square before the move changes it
+        let captured: Option<Piece> = match game.parse_square(_to) {
+            Ok(position) => game.board.get(&position).filter(|piece| piece.color != game.active_color).cloned(),
+            Err(_) => None
+        };
+
+        self.board_mut(_board).try_make_move(_from, _to)?;
+        self.moves.push(_board);
+
+        if let Some(captured) = captured {
+            let color: Color = captured.color.clone();
+            self.board_mut(other(_board)).pocket_piece(&color, captured);
+        }
+
+        self.finish_move(_board);
+        return Ok(self.get_board(_board).get_game_state());
+    }
+
+    /// Drop a piece from the pocket of the side to move on one of the boards.
+    pub fn try_drop(&mut self, _board: BughouseBoard, _role: Role, _to: &str) -> Result<GameState, MoveError> {
+        self.board_mut(_board).try_drop(_role, _to)?;
+        self.moves.push(_board);
+
+        self.finish_move(_board);
+        return Ok(self.get_board(_board).get_game_state());
+    }
+
+    /// Choose the piece a pawn promotes to on one of the boards.
+    pub fn try_set_promotion(&mut self, _board: BughouseBoard, _pos: &str, _role: Role) -> Result<GameState, MoveError> {
+        self.board_mut(_board).try_set_promotion(_pos, _role)?;
+
+        self.finish_move(_board);
+        return Ok(self.get_board(_board).get_game_state());
+    }
+
+    /// Give up the match for a team. The other team wins.
+    pub fn resign(&mut self, _team: BughouseTeam) {
+        if self.get_winner().is_some() {
+            return;
+        }
+
+        self.board_a.resign(team_color(_team, BughouseBoard::A));
+        self.board_b.resign(team_color(_team, BughouseBoard::B));
+    }
+
+    /// Get the winning team once a king has been captured or a team resigned.
+    pub fn get_winner(&self) -> Option<BughouseTeam> {
+        match self.board_a.get_winner() {
+            Some(color) => return Some(team(BughouseBoard::A, &color)),
+            None => ()
+        }
+        match self.board_b.get_winner() {
+            Some(color) => return Some(team(BughouseBoard::B, &color)),
+            None => return None
+        }
+    }
+
+    /// Export the match in Bughouse Portable Game Notation.
+    pub fn to_bpgn(&self) -> String {
+
+        // Team A wins with white on board A
+        let result: &str = match self.get_winner() {
+            Some(BughouseTeam::A) => "1-0",
+            Some(BughouseTeam::B) => "0-1",
+            None => "*"
+        };
+
+        let mut bpgn: String = String::new();
+        bpgn.push_str("[Event \"Chess royale\"]\n");
+        bpgn.push_str("[Site \"?\"]\n");
+        bpgn.push_str("[Date \"????.??.??\"]\n");
+        bpgn.push_str("[WhiteA \"?\"]\n");
+        bpgn.push_str("[BlackA \"?\"]\n");
+        bpgn.push_str("[WhiteB \"?\"]\n");
+        bpgn.push_str("[BlackB \"?\"]\n");
+        bpgn.push_str(&format!("[Result \"{}\"]\n", result));
+        bpgn.push('\n');
+
+        // Take the moves from the histories of the boards in the order they were made,
+        // numbered per board with an upper case letter for white and lower case for black
+        let histories: [Vec<String>; 2] = [self.board_a.get_history(), self.board_b.get_history()];
+        let letters: [[char; 2]; 2] = [['A', 'a'], ['B', 'b']];
+        let mut played: [usize; 2] = [0, 0];
+
+        for board in self.moves.iter() {
+            let index: usize = match board {
+                BughouseBoard::A => 0,
+                BughouseBoard::B => 1
+            };
+            let count: usize = played[index];
+            played[index] += 1;
+
+            bpgn.push_str(&format!("{}{}. {} ", count / 2 + 1, letters[index][count % 2], histories[index][count]));
+        }
+        bpgn.push_str(result);
+        bpgn.push('\n');
+
+        return bpgn;
+    }
+
+    fn board_mut(&mut self, _board: BughouseBoard) -> &mut Game {
+        match _board {
+            BughouseBoard::A => return &mut self.board_a,
+            BughouseBoard::B => return &mut self.board_b
+        }
+    }
+
+    // Once a king falls on one board, the losing team gives up the other
+    fn finish_move(&mut self, _board: BughouseBoard) {
+        let winner: Color = match self.get_board(_board).get_winner() {
+            Some(color) => color,
+            None => return
+        };
+
+        let loser: BughouseTeam = match team(_board, &winner) {
+            BughouseTeam::A => BughouseTeam::B,
+            BughouseTeam::B => BughouseTeam::A
+        };
+        let other: BughouseBoard = other(_board);
+        if self.get_board(other).get_game_state() != GameState::GameOver {
+            self.board_mut(other).resign(team_color(loser, other));
+        }
+    }
+}
+
+impl Default for Bughouse {
+    fn default() -> Self {
+        return Bughouse::new();
+    }
+}
+
+// The board next to the given one
+fn other(_board: BughouseBoard) -> BughouseBoard {
+    match _board {
+        BughouseBoard::A => return BughouseBoard::B,
+        BughouseBoard::B => return BughouseBoard::A
+    }
+}
+
+// Team playing a color on a board
+fn team(_board: BughouseBoard, _color: &Color) -> BughouseTeam {
+    match (_board, _color) {
+        (BughouseBoard::A, Color::White) | (BughouseBoard::B, Color::Black) => return BughouseTeam::A,
+        _ => return BughouseTeam::B
+    }
+}
+
+// Color a team plays on a board
+fn team_color(_team: BughouseTeam, _board: BughouseBoard) -> Color {
+    match (_team, _board) {
+        (BughouseTeam::A, BughouseBoard::A) | (BughouseTeam::B, BughouseBoard::B) => return Color::White,
+        _ => return Color::Black
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use crate::fen::FenError;
+use crate::{Color, Dimensions, Game, Piece, Position, Role};
+
+/* Castling, with the Chess960 rules which contain the standard ones.
+ *
+ * - The king and the rook must both be unmoved and stand on their back rank.
+ *   `Piece::has_moved` is the only record of castling rights.
+ * - Castling towards the h-file puts the king on g and the rook on f,
+ *   towards the a-file the king on c and the rook on d. On wider boards
+ *   the king ends up next to the corner the same way, e.g. on i and the
+ *   rook on h in Capablanca chess.
+ * - Every square the king and the rook pass or land on must be empty,
+ *   apart from the two castling pieces themselves.
+ * - Kings may be captured in Chess royale, so castling out of or through
+ *   an attacked square is allowed.
+ *
+ * A castling move is written as the king moving onto its own rook, which is
+ * unambiguous in Chess960. Standard games also accept the king moving onto
+ * its castling square, e.g. e1 to g1.
+ */
+
+impl Game {
+
+    // Rooks the king can castle with, as the squares they stand on
+    pub(crate) fn castling_moves(&self, king: &Piece) -> Vec<Position> {
+        let mut moves: Vec<Position> = Vec::new();
+
+        let dimensions: Dimensions = self.get_dimensions();
+        if !self.allows_castling() || king.role != Role::King || king.has_moved || king.position.row != back_rank(&king.color, &dimensions) {
+            return moves;
+        }
+
+        for rook in self.board.values() {
+            if rook.role != Role::Rook || rook.color != king.color || rook.has_moved || rook.position.row != king.position.row {
+                continue;
+            }
+
+            let (king_to, rook_to) = destinations(&king.position, &rook.position, &dimensions);
+            let columns: [i8; 4] = [king.position.column, rook.position.column, king_to, rook_to];
+            let first: i8 = *columns.iter().min().unwrap();
+            let last: i8 = *columns.iter().max().unwrap();
+
+            // Only the king and the rook may stand between the outermost squares
+            let path_clear: bool = (first..=last).all(|column| {
+                let square: Position = Position { row: king.position.row, column };
+                if self.duck.as_ref() == Some(&square) {
+                    return false;
+                }
+                return !self.board.contains_key(&square) || square == king.position || square == rook.position;
+            });
+
+            if path_clear {
+                moves.push(rook.position.clone());
+            }
+        }
+
+        return moves;
+    }
+
+    // Translate the king move of standard chess, e.g. e1 to g1, into the king moving onto its rook
+    pub(crate) fn castling_target(&self, from: &Position, to: &Position) -> Position {
+        let dimensions: Dimensions = self.get_dimensions();
+        let king_side: (i8, i8) = destinations(from, &Position { row: from.row, column: dimensions.columns }, &dimensions);
+        let queen_side: (i8, i8) = destinations(from, &Position { row: from.row, column: 1 }, &dimensions);
+
+        if self.chess960 || from.column != king_column(&dimensions) || from.row != to.row || (to.column != king_side.0 && to.column != queen_side.0) {
+            return to.clone();
+        }
+
+        match self.board.get(from) {
+            Some(piece) if piece.role == Role::King && !piece.has_moved => {
+                let column: i8 = if to.column > from.column { dimensions.columns } else { 1 };
+                return Position { row: from.row, column };
+            },
+            _ => return to.clone()
+        }
+    }
+
+    // Whether a legal move is a castling move
+    pub(crate) fn is_castling(&self, from: &Position, to: &Position) -> bool {
+        match (self.board.get(from), self.board.get(to)) {
+            (Some(king), Some(rook)) => return king.role == Role::King && rook.role == Role::Rook && king.color == rook.color,
+            _ => return false
+        }
+    }
+
+    // Square the king lands on when castling with the rook on the given square
+    pub(crate) fn castling_square(&self, from: &Position, to: &Position) -> Position {
+        let (king_to, _) = destinations(from, to, &self.get_dimensions());
+        return Position { row: from.row, column: king_to };
+    }
+
+    // Move king and rook to their castling squares and write down the move
+    pub(crate) fn castle(&mut self, from: &Position, to: &Position) {
+        let (king_to, rook_to) = destinations(from, to, &self.get_dimensions());
+        let mut king: Piece = self.take_piece(from).unwrap();
+        let mut rook: Piece = self.take_piece(to).unwrap();
+
+        if self.record {
+            let notation: &str = if to.column > from.column { "O-O" } else { "O-O-O" };
+            self.history.push(String::from(notation));
+        }
+        self.halfmove_clock += 1;
+
+        king.has_moved = true;
+        king.position = Position { row: from.row, column: king_to };
+        rook.has_moved = true;
+        rook.position = Position { row: from.row, column: rook_to };
+
+        self.put_piece(king);
+        self.put_piece(rook);
+    }
+
+    // Castling field of FEN. X-FEN writes K and Q for the outermost rooks and
+    // files for the others, Shredder-FEN always writes files.
+    pub(crate) fn castling_field(&self, shredder: bool) -> String {
+        if !self.allows_castling() {
+            return String::from("-");
+        }
+
+        let mut field: String = String::new();
+
+        for color in [Color::White, Color::Black].iter() {
+            let row: i8 = back_rank(color, &self.get_dimensions());
+            let king: &Piece = match self.board.values().find(|piece| piece.role == Role::King && piece.color == *color && piece.position.row == row && !piece.has_moved) {
+                Some(king) => king,
+                None => continue
+            };
+
+            // Unmoved rooks on the back rank, h-file side first
+            let mut rooks: Vec<i8> = self.board.values()
+                .filter(|piece| piece.role == Role::Rook && piece.color == *color && piece.position.row == row && !piece.has_moved)
+                .map(|rook| rook.position.column)
+                .collect();
+            rooks.sort_unstable_by(|a, b| b.cmp(a));
+
+            for column in rooks.iter() {
+                let king_side: bool = *column > king.position.column;
+                let outermost: bool = if king_side { *column == rooks[0] } else { *column == rooks[rooks.len() - 1] };
+
+                let letter: char = if shredder || !outermost {
+                    (b'A' + (*column - 1) as u8) as char
+                } else if king_side {
+                    'K'
+                } else {
+                    'Q'
+                };
+
+                match color {
+                    Color::White => field.push(letter),
+                    Color::Black => field.push(letter.to_ascii_lowercase())
+                }
+            }
+        }
+
+        if field.is_empty() {
+            field.push('-');
+        }
+        return field;
+    }
+}
+
+// Read the castling field of X-FEN or Shredder-FEN into the has_moved flags of
+// kings and rooks. Returns whether the rights only make sense in Chess960.
+pub(crate) fn apply_castling_field(board: &mut HashMap<Position, Piece>, dimensions: &Dimensions, field: &str) -> Result<bool, FenError> {
+
+    // Kings and rooks without rights count as moved
+    for piece in board.values_mut() {
+        if piece.role == Role::King || piece.role == Role::Rook {
+            piece.has_moved = true;
+        }
+    }
+
+    if field == "-" {
+        return Ok(false);
+    }
+
+    let mut chess960: bool = false;
+    for letter in field.chars() {
+        let color: Color = if letter.is_ascii_uppercase() { Color::White } else { Color::Black };
+        let row: i8 = back_rank(&color, dimensions);
+
+        let king: Position = match board.values().find(|piece| piece.role == Role::King && piece.color == color && piece.position.row == row) {
+            Some(king) => king.position.clone(),
+            None => return Err(FenError::InvalidCastling)
+        };
+        let rooks: Vec<i8> = board.values()
+            .filter(|piece| piece.role == Role::Rook && piece.color == color && piece.position.row == row)
+            .map(|rook| rook.position.column)
+            .collect();
+
+        // K and Q name the outermost rook on that side, letters name the file
+        let column: Option<i8> = match letter.to_ascii_lowercase() {
+            'k' => rooks.iter().filter(|column| **column > king.column).max().cloned(),
+            'q' => rooks.iter().filter(|column| **column < king.column).min().cloned(),
+            file @ 'a'..='z' if ((file as u8 - b'a' + 1) as i8) <= dimensions.columns => {
+                chess960 = true;
+                rooks.iter().find(|column| **column == (file as u8 - b'a' + 1) as i8).cloned()
+            },
+            _ => return Err(FenError::InvalidCastling)
+        };
+
+        let rook: Position = match column {
+            Some(column) => Position { row, column },
+            None => return Err(FenError::InvalidCastling)
+        };
+        if king.column != king_column(dimensions) || (rook.column != 1 && rook.column != dimensions.columns) {
+            chess960 = true;
+        }
+
+        board.get_mut(&king).unwrap().has_moved = false;
+        board.get_mut(&rook).unwrap().has_moved = false;
+    }
+
+    return Ok(chess960);
+}
+
+// Row of the pieces of a color at the start
+fn back_rank(color: &Color, dimensions: &Dimensions) -> i8 {
+    match color {
+        Color::White => return 1,
+        Color::Black => return dimensions.rows
+    }
+}
+
+// Column of the king at the start, e on 8x8 and f on 10 files
+fn king_column(dimensions: &Dimensions) -> i8 {
+    return dimensions.columns / 2 + 1;
+}
+
+// Columns the king and the rook end up on
+fn destinations(king: &Position, rook: &Position, dimensions: &Dimensions) -> (i8, i8) {
+    if rook.column > king.column {
+        return (dimensions.columns - 1, dimensions.columns - 2);
+    } else {
+        return (3, 4);
+    }
+}
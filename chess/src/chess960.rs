@@ -0,0 +1,99 @@
+use std::collections::hash_map::RandomState;
+use std::convert::TryInto;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::{Game, Role};
+
+/* Chess960 (Fischer Random) start positions.
+ *
+ * The 960 back ranks are numbered the way Scharnagl did, 518 is the
+ * standard setup. Both colors get the same back rank, so the black king
+ * stands on the same file as the white king.
+ */
+
+/// Number of Chess960 start positions.
+pub const CHESS960_POSITIONS: u16 = 960;
+
+// Knight squares among the five empty squares left after the bishops and the queen
+const KNIGHTS: [(usize, usize); 10] = [(0, 1), (0, 2), (0, 3), (0, 4), (1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)];
+
+/// Get the back rank with the given Scharnagl number, from the a-file to the h-file.
+/// Number 518 is the standard setup.
+pub fn chess960_back_rank(index: u16) -> Option<[Role; 8]> {
+    if index >= CHESS960_POSITIONS {
+        return None;
+    }
+
+    let mut rank: [Option<Role>; 8] = Default::default();
+    let mut rest: usize = index as usize;
+
+    // Bishop on a light square (b, d, f or h), then on a dark one (a, c, e or g)
+    rank[(rest % 4) * 2 + 1] = Some(Role::Bishop);
+    rest /= 4;
+    rank[(rest % 4) * 2] = Some(Role::Bishop);
+    rest /= 4;
+
+    // Queen on one of the six empty squares
+    let queen: usize = rest % 6;
+    rest /= 6;
+    place(&mut rank, queen, Role::Queen);
+
+    // Knights, the second index shifts by one as the first knight fills a square
+    let (first, second) = KNIGHTS[rest];
+    place(&mut rank, first, Role::Knight);
+    place(&mut rank, second - 1, Role::Knight);
+
+    // The king stands between the rooks on the three squares left
+    place(&mut rank, 0, Role::Rook);
+    place(&mut rank, 0, Role::King);
+    place(&mut rank, 0, Role::Rook);
+
+    let roles: Vec<Role> = rank.iter().map(|role| role.clone().unwrap()).collect();
+    return Some(roles.try_into().unwrap());
+}
+
+// Put a role on the nth empty square of a rank
+fn place(rank: &mut [Option<Role>; 8], nth: usize, role: Role) {
+    let column: usize = rank.iter()
+        .enumerate()
+        .filter(|(_, square)| square.is_none())
+        .map(|(column, _)| column)
+        .nth(nth)
+        .unwrap();
+    rank[column] = Some(role);
+}
+
+impl Game {
+
+    /// Initialises a Chess960 board. `index` is the Scharnagl number of the
+    /// back rank, from 0 to 959.
+    pub fn new_chess960(index: u16) -> Option<Game> {
+        match chess960_back_rank(index) {
+            Some(rank) => return Some(Self::from_back_rank(&rank, true)),
+            None => return None
+        }
+    }
+
+    /// Initialises a Chess960 board picked from a seed. The same seed always
+    /// gives the same start position.
+    pub fn new_chess960_seeded(seed: u64) -> Game {
+        let index: u16 = (splitmix64(seed) % CHESS960_POSITIONS as u64) as u16;
+        return Self::new_chess960(index).unwrap();
+    }
+
+    /// Initialises a Chess960 board with a start position picked at random.
+    pub fn new_chess960_random() -> Game {
+
+        // RandomState is seeded from the operating system
+        let seed: u64 = RandomState::new().build_hasher().finish();
+        return Self::new_chess960_seeded(seed);
+    }
+}
+
+// Spread neighbouring seeds over the whole range
+fn splitmix64(seed: u64) -> u64 {
+    let mut z: u64 = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    return z ^ (z >> 31);
+}
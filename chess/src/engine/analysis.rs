@@ -0,0 +1,147 @@
+use std::fmt;
+
+use super::limits::{Limits, SearchControl};
+use super::search::{play, Engine, WIN_BOUND, WIN_SCORE};
+use crate::{Game, Move};
+
+/* MultiPV analysis.
+ *
+ * `Engine::analyse` finds the best few moves of a position instead of only
+ * the best one. At every depth it searches the root once per line, each
+ * time leaving out the first moves of the lines found before, so the
+ * second line is the best move but the first, and so on. The helper
+ * threads of Lazy SMP fill the table as in an ordinary search.
+ *
+ * Every line holds its principal variation both as moves and in the
+ * notation of the game history, played out from the analysed position.
+ * Scores are seen from the side to move in that position.
+ */
+
+/// Score of a line for the side to move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+
+    // Moves until a king is captured, negative when the own king is
+    KingCapture(i32)
+}
+
+/// One of the best moves of a position and the moves expected to follow it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub score: Score,
+    pub depth: u8,
+
+    // Principal variation, starting with the move of the line
+    pub pv: Vec<Move>,
+
+    // Principal variation in the notation of the game history, e.g. "Nf3"
+    pub san: Vec<String>
+}
+
+impl Score {
+
+    /// Get the score of a search result, see `WIN_SCORE`.
+    pub fn from_search(score: i32) -> Score {
+        if score.abs() > WIN_BOUND {
+            let moves: i32 = (WIN_SCORE - score.abs() + 1) / 2;
+            return Score::KingCapture(if score > 0 { moves } else { -moves });
+        }
+        return Score::Centipawns(score);
+    }
+
+    /// Get the score as UCI writes it, king captures as mates.
+    pub fn to_uci(&self) -> String {
+        match self {
+            Score::Centipawns(centipawns) => return format!("cp {}", centipawns),
+            Score::KingCapture(moves) => return format!("mate {}", moves)
+        }
+    }
+}
+
+impl Line {
+
+    /// Get the move of the line.
+    pub fn get_move(&self) -> &Move {
+        return &self.pv[0];
+    }
+}
+
+impl Engine {
+
+    /// Find the best `multipv` moves of a game within the limits, best line first.
+    /// Fewer lines come back when there are fewer moves.
+    pub fn analyse(&self, game: &Game, limits: Limits, multipv: usize) -> Vec<Line> {
+        return self.analyse_with(game, &SearchControl::new(limits), multipv, &mut |_| ());
+    }
+
+    /// Same as `analyse`, stopped through the given control, and telling `report` the
+    /// lines of every depth finished.
+    pub fn analyse_with(&self, game: &Game, control: &SearchControl, multipv: usize, report: &mut dyn FnMut(&[Line])) -> Vec<Line> {
+        let lines: Option<Vec<Line>> = self.run(game, control, |searcher, depth| {
+            let mut lines: Vec<Line> = Vec::new();
+            let mut excluded: Vec<Move> = Vec::new();
+
+            while lines.len() < multipv {
+                let (score, mut pv) = match searcher.search_root(game, depth, &excluded) {
+                    Some(found) => found,
+
+                    // Out of moves, unless the search was stopped
+                    None if control.should_stop() => return None,
+                    None => break
+                };
+
+                searcher.extend_pv(game, &mut pv, depth);
+                excluded.push(pv[0].clone());
+                lines.push(Line { score: Score::from_search(score), depth, san: notation(game, &pv), pv });
+            }
+
+            if lines.is_empty() {
+                return None;
+            }
+            report(&lines);
+            return Some(lines);
+        });
+
+        return lines.unwrap_or_default();
+    }
+}
+
+/// Find the best `multipv` moves of a game with a new engine on one thread, see `Engine::analyse`.
+pub fn analyse(game: &Game, limits: Limits, multipv: usize) -> Vec<Line> {
+    return Engine::new().analyse(game, limits, multipv);
+}
+
+/// Get the score of a search result as UCI writes it, "cp 35" or "mate -2".
+pub fn uci_score(score: i32) -> String {
+    return Score::from_search(score).to_uci();
+}
+
+// The moves of a principal variation in the notation of the game history
+fn notation(game: &Game, pv: &[Move]) -> Vec<String> {
+    let mut notation: Vec<String> = Vec::new();
+    let mut position: Game = game.clone();
+
+    for _move in pv {
+        position = match play(&position, _move) {
+            Some(next) => next,
+            None => break
+        };
+        match position.history.last() {
+            Some(last) => notation.push(last.clone()),
+            None => break
+        }
+    }
+
+    return notation;
+}
+
+// Pawns with two decimals and king captures with #, e.g. "+0.35" or "#-2"
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => write!(f, "{:+.2}", *centipawns as f64 / 100.0),
+            Score::KingCapture(moves) => write!(f, "#{}", moves)
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Deepest depth a search goes to when nothing else stops it.
+pub const MAX_DEPTH: u8 = 64;
+
+/// When a search has to stop. Limits left out do not stop it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Limits {
+    pub depth: Option<u8>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>
+}
+
+/// State of a running search: when it started, the nodes it visited and a
+/// stop flag, which may be set from other threads.
+pub struct SearchControl {
+    limits: Limits,
+    start: Instant,
+    nodes: AtomicU64,
+    stop: Arc<AtomicBool>
+}
+
+impl Limits {
+
+    /// Search to a fixed depth.
+    pub fn depth(depth: u8) -> Limits {
+        return Limits { depth: Some(depth), ..Limits::default() };
+    }
+
+    /// Search for a fixed time.
+    pub fn time(time: Duration) -> Limits {
+        return Limits { time: Some(time), ..Limits::default() };
+    }
+}
+
+impl SearchControl {
+
+    /// Start the clock for a search with the given limits.
+    pub fn new(limits: Limits) -> SearchControl {
+        return SearchControl { limits, start: Instant::now(), nodes: AtomicU64::new(0), stop: Arc::new(AtomicBool::new(false)) };
+    }
+
+    /// Get the limits of the search.
+    pub fn get_limits(&self) -> &Limits {
+        return &self.limits;
+    }
+
+    /// Get a flag which stops the search once set, e.g. from another thread.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        return self.stop.clone();
+    }
+
+    /// Stop the search.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Count a visited node.
+    pub fn count_node(&self) {
+        self.nodes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the nodes visited so far.
+    pub fn nodes(&self) -> u64 {
+        return self.nodes.load(Ordering::Relaxed);
+    }
+
+    /// Get the time since the search started.
+    pub fn elapsed(&self) -> Duration {
+        return self.start.elapsed();
+    }
+
+    /// Whether the search was stopped or ran out of nodes or time. The search
+    /// checks it now and then and gives up the depth it is busy with.
+    pub fn should_stop(&self) -> bool {
+        if self.stop.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let out_of_nodes: bool = match self.limits.nodes {
+            Some(nodes) => self.nodes() >= nodes,
+            None => false
+        };
+        let out_of_time: bool = match self.limits.time {
+            Some(time) => self.elapsed() >= time,
+            None => false
+        };
+
+        if out_of_nodes || out_of_time {
+            self.stop();
+            return true;
+        }
+        return false;
+    }
+}
+
+/// Run a search at depth 1, 2, 3, ... up to the depth limit, until it is stopped or
+/// the search of a depth returns nothing. Returns the result of the deepest depth
+/// searched to the end, a depth cut short by the limits is thrown away.
+pub fn iterative_deepening<T, F>(control: &SearchControl, mut search: F) -> Option<T>
+    where F: FnMut(u8) -> Option<T> {
+    let mut result: Option<T> = None;
+    let last: u8 = control.get_limits().depth.unwrap_or(MAX_DEPTH);
+
+    for depth in 1..=last {
+        let found: Option<T> = search(depth);
+
+        // The first depth always counts, there has to be some answer
+        if control.should_stop() && result.is_some() {
+            break;
+        }
+        match found {
+            Some(found) => result = Some(found),
+            None => break
+        }
+        if control.should_stop() {
+            break;
+        }
+    }
+
+    return result;
+}
@@ -0,0 +1,32 @@
+mod limits;
+mod table;
+mod zobrist;
+mod search;
+mod analysis;
+mod uci;
+
+pub use limits::{iterative_deepening, Limits, SearchControl, MAX_DEPTH};
+pub use table::{Bound, Entry, TranspositionTable};
+pub use search::{Engine, SearchResult, WIN_SCORE};
+pub use analysis::{analyse, uci_score, Line, Score};
+pub use uci::Uci;
+pub(crate) use zobrist::board_hash;
+
+/* Building blocks for searching games.
+ *
+ * - `Game::zobrist_hash` identifies a position by everything the rules
+ *   look at, but not the move counters.
+ * - `TranspositionTable` stores what a search learned about positions,
+ *   keyed by that hash. Its size is given in megabytes, and it can be
+ *   shared between threads without locks.
+ * - `Limits`, `SearchControl` and `iterative_deepening` run a search
+ *   depth after depth until a depth, node or time limit is reached or it
+ *   is stopped from outside.
+ *
+ * None of them know how a search works, so engines with other
+ * algorithms can use them as well. `Engine` is the alpha-beta search
+ * built on them, running on any number of threads, and `Uci` puts it
+ * behind the Universal Chess Interface. `Engine::analyse` reports the
+ * best few lines of a position for review, with their scores and
+ * principal variations.
+ */
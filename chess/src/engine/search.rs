@@ -0,0 +1,484 @@
+use std::cmp;
+use std::thread;
+
+use super::limits::{iterative_deepening, Limits, SearchControl};
+use super::table::{Bound, TranspositionTable};
+use crate::{Game, GameState, Move, Role};
+
+/* Alpha-beta search with Lazy SMP.
+ *
+ * Each thread runs its own iterative deepening alpha-beta search over the
+ * same position, and all of them share one transposition table. The
+ * helper threads do not report anything, they fill the table with results
+ * the main thread then finds instead of searching them again. Every other
+ * helper searches one ply deeper, so the threads spread out over the tree.
+ * With one thread the search is deterministic: the same position and
+ * limits without a time limit always give the same result.
+ *
+ * Positions are scored by material from the side to move, after the
+ * captures have been played out in a quiescence search. Capturing a king
+ * ends the game, so scores near `WIN_SCORE` count the plies to the king
+ * capture, a king taken in 3 plies scores WIN_SCORE - 3.
+ *
+ * The search plays piece moves. It does not drop pieces, and in Duck
+ * chess it puts the duck on the first free square. Moves are played on
+ * copies of the position alone, which leave the history and notation of
+ * the game behind.
+ */
+
+/// Score of a king captured right now, captures further ahead score one less per ply.
+pub const WIN_SCORE: i32 = 30000;
+
+// Scores this close to WIN_SCORE are king captures
+pub(crate) const WIN_BOUND: i32 = WIN_SCORE - 1000;
+
+// Deepest ply quiescence goes to
+const MAX_PLY: i32 = 128;
+
+// Roles a pawn is tried as, the variant leaves out those it does not allow
+const PROMOTIONS: [Role; 7] = [Role::Queen, Role::Knight, Role::Rook, Role::Bishop, Role::Archbishop, Role::Chancellor, Role::King];
+
+/// What a search found at the deepest depth it finished.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+
+    // Score for the side to move, see WIN_SCORE
+    pub score: i32,
+    pub depth: u8,
+
+    // Nodes of all threads
+    pub nodes: u64,
+
+    // Principal variation, starting with the best move
+    pub pv: Vec<Move>
+}
+
+/// Searches games for the best move with a number of threads and a
+/// transposition table, which keeps what it learned between searches.
+pub struct Engine {
+    table: TranspositionTable,
+    threads: usize,
+    hash: usize
+}
+
+// One thread of a search
+pub(crate) struct Searcher<'a> {
+    table: &'a TranspositionTable,
+    control: &'a SearchControl
+}
+
+impl Engine {
+
+    /// Create an engine searching with one thread and a table of 16 megabytes.
+    pub fn new() -> Engine {
+        return Engine { table: TranspositionTable::new(16), threads: 1, hash: 16 };
+    }
+
+    /// Set the number of threads, at least one.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = cmp::max(threads, 1);
+    }
+
+    /// Get the number of threads.
+    pub fn get_threads(&self) -> usize {
+        return self.threads;
+    }
+
+    /// Set the size of the transposition table in megabytes. Forgets everything stored.
+    pub fn set_hash(&mut self, megabytes: usize) {
+        self.hash = megabytes;
+        self.table = TranspositionTable::new(megabytes);
+    }
+
+    /// Get the size of the transposition table in megabytes.
+    pub fn get_hash(&self) -> usize {
+        return self.hash;
+    }
+
+    /// Get the transposition table.
+    pub fn get_table(&self) -> &TranspositionTable {
+        return &self.table;
+    }
+
+    /// Forget what earlier searches learned, before a new game.
+    pub fn new_game(&mut self) {
+        self.table.clear();
+    }
+
+    /// Search a game for the best move within the limits.
+    pub fn search(&self, game: &Game, limits: Limits) -> SearchResult {
+        return self.search_with(game, &SearchControl::new(limits), &mut |_| ());
+    }
+
+    /// Same as `search`, stopped through the given control, and telling `report` the
+    /// result of every depth the main thread finishes.
+    pub fn search_with(&self, game: &Game, control: &SearchControl, report: &mut dyn FnMut(&SearchResult)) -> SearchResult {
+        let result: Option<SearchResult> = self.run(game, control, |searcher, depth| {
+            let (score, mut pv) = searcher.search_root(game, depth, &[])?;
+            searcher.extend_pv(game, &mut pv, depth);
+            let found: SearchResult = SearchResult { best_move: pv.first().cloned(), score, depth, nodes: control.nodes(), pv };
+            report(&found);
+            return Some(found);
+        });
+
+        // Stopped before the first depth was done, any move is better than none
+        let mut result: SearchResult = match result {
+            Some(result) => result,
+            None => {
+                let best_move: Option<Move> = legal_moves(game).into_iter().next();
+                SearchResult { best_move: best_move.clone(), score: 0, depth: 0, nodes: 0, pv: best_move.into_iter().collect() }
+            }
+        };
+        result.nodes = control.nodes();
+        return result;
+    }
+
+    // Run the helper threads and iterative deepening of the main thread with the given search
+    // of a depth, and return what the main thread found at the deepest depth
+    pub(crate) fn run<T, F>(&self, game: &Game, control: &SearchControl, mut search: F) -> Option<T>
+        where F: FnMut(&Searcher, u8) -> Option<T> {
+        self.table.new_search();
+
+        let mut result: Option<T> = None;
+        thread::scope(|scope| {
+
+            // Helpers search until the main thread is done
+            for helper in 1..self.threads {
+                let searcher: Searcher = Searcher::new(&self.table, control);
+                let game: Game = game.clone();
+                scope.spawn(move || {
+                    iterative_deepening(control, |depth| {
+                        let depth: u8 = depth.saturating_add((helper % 2) as u8);
+                        return searcher.search_root(&game, depth, &[]);
+                    });
+                });
+            }
+
+            let searcher: Searcher = Searcher::new(&self.table, control);
+            result = iterative_deepening(control, |depth| search(&searcher, depth));
+            control.stop();
+        });
+
+        return result;
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        return Engine::new();
+    }
+}
+
+impl<'a> Searcher<'a> {
+
+    pub(crate) fn new(table: &'a TranspositionTable, control: &'a SearchControl) -> Searcher<'a> {
+        return Searcher { table, control };
+    }
+
+    // Search every root move but the excluded ones, and return the best score with its
+    // principal variation. Nothing when stopped or there is no move to search.
+    pub(crate) fn search_root(&self, game: &Game, depth: u8, excluded: &[Move]) -> Option<(i32, Vec<Move>)> {
+        let mut best: Option<(i32, Vec<Move>)> = None;
+        let mut alpha: i32 = -WIN_SCORE - 1;
+
+        let key: u64 = game.zobrist_hash();
+        let hint: Option<Move> = self.table.probe(key).and_then(|entry| entry.best_move);
+
+        for candidate in self.ordered_moves(game, hint.as_ref()) {
+            if excluded.contains(&candidate) {
+                continue;
+            }
+            let child: Game = match make_move(game, &candidate) {
+                Some(child) => child,
+                None => continue
+            };
+
+            let mut line: Vec<Move> = Vec::new();
+            let score: i32 = -self.negamax(&child, depth - 1, -WIN_SCORE - 1, -alpha, 1, &mut line)?;
+            if score > alpha {
+                alpha = score;
+                line.insert(0, candidate);
+                best = Some((score, line));
+            }
+        }
+
+        // Only the full root knows the best move of the position
+        if let Some((score, line)) = &best {
+            if excluded.is_empty() {
+                self.table.store(key, depth, *score, Bound::Exact, line.first().cloned());
+            }
+        }
+        return best;
+    }
+
+    // A score the table already knew leaves the principal variation short, the table
+    // knows the best moves of the positions on it as well
+    pub(crate) fn extend_pv(&self, game: &Game, pv: &mut Vec<Move>, depth: u8) {
+        let mut position: Game = game.clone();
+        for _move in pv.iter() {
+            position = match make_move(&position, _move) {
+                Some(next) => next,
+                None => return
+            };
+        }
+
+        while pv.len() < depth as usize {
+            let next: Move = match self.table.probe(position.zobrist_hash()).and_then(|entry| entry.best_move) {
+                Some(next) if legal_moves(&position).contains(&next) => next,
+                _ => return
+            };
+            position = match make_move(&position, &next) {
+                Some(position) => position,
+                None => return
+            };
+            pv.push(next);
+        }
+    }
+
+    // Score of the position for the side to move, filling in the principal variation
+    fn negamax(&self, game: &Game, depth: u8, alpha: i32, beta: i32, ply: i32, pv: &mut Vec<Move>) -> Option<i32> {
+        pv.clear();
+
+        // The side to move has lost
+        if game.state == GameState::GameOver {
+            return Some(-(WIN_SCORE - ply));
+        }
+        if depth == 0 {
+            return self.quiescence(game, alpha, beta, ply);
+        }
+
+        self.control.count_node();
+        if self.control.should_stop() {
+            return None;
+        }
+
+        let key: u64 = game.zobrist_hash();
+        let mut alpha: i32 = alpha;
+        let mut hint: Option<Move> = None;
+
+        if let Some(entry) = self.table.probe(key) {
+            hint = entry.best_move.clone();
+
+            if entry.depth >= depth {
+                let score: i32 = from_table(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return Some(score),
+                    Bound::Lower if score >= beta => return Some(score),
+                    Bound::Upper if score <= alpha => return Some(score),
+                    _ => ()
+                }
+            }
+        }
+
+        let original_alpha: i32 = alpha;
+        let mut best: i32 = -WIN_SCORE - 1;
+        let mut best_move: Option<Move> = None;
+
+        for candidate in self.ordered_moves(game, hint.as_ref()) {
+            let child: Game = match make_move(game, &candidate) {
+                Some(child) => child,
+                None => continue
+            };
+
+            let mut line: Vec<Move> = Vec::new();
+            let score: i32 = -self.negamax(&child, depth - 1, -beta, -alpha, ply + 1, &mut line)?;
+
+            if score > best {
+                best = score;
+                best_move = Some(candidate.clone());
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(candidate);
+                pv.append(&mut line);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // Nothing to move, neither side gains
+        if best_move.is_none() {
+            return Some(0);
+        }
+
+        let bound: Bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(key, depth, to_table(best, ply), bound, best_move);
+
+        return Some(best);
+    }
+
+    // Play out captures which do not lose material until the position is quiet
+    fn quiescence(&self, game: &Game, alpha: i32, beta: i32, ply: i32) -> Option<i32> {
+        if game.state == GameState::GameOver {
+            return Some(-(WIN_SCORE - ply));
+        }
+
+        self.control.count_node();
+        if self.control.should_stop() {
+            return None;
+        }
+
+        // The side to move may also not capture at all
+        let standing: i32 = evaluate(game);
+        if standing >= beta || ply >= MAX_PLY {
+            return Some(standing);
+        }
+        let mut alpha: i32 = cmp::max(alpha, standing);
+
+        let mut captures: Vec<(i32, Move)> = legal_moves(game).into_iter()
+            .filter(|candidate| game.board.contains_key(&candidate.to) && game.board[&candidate.to].color != game.active_color)
+            .map(|candidate| (game.see(&candidate), candidate))
+            .filter(|(gain, _)| *gain >= 0)
+            .collect();
+        captures.sort_by_key(|(gain, _)| cmp::Reverse(*gain));
+
+        for (_, candidate) in captures {
+            let child: Game = match make_move(game, &candidate) {
+                Some(child) => child,
+                None => continue
+            };
+
+            let score: i32 = -self.quiescence(&child, -beta, -alpha, ply + 1)?;
+            if score >= beta {
+                return Some(score);
+            }
+            alpha = cmp::max(alpha, score);
+        }
+
+        return Some(alpha);
+    }
+
+    // Moves in the order they are searched: the best move the table knows, captures
+    // by what they win and then the quiet moves
+    fn ordered_moves(&self, game: &Game, hint: Option<&Move>) -> Vec<Move> {
+        let mut scored: Vec<(i32, Move)> = legal_moves(game).into_iter().map(|candidate| {
+            let order: i32 = if Some(&candidate) == hint {
+                i32::MAX
+            } else if game.board.contains_key(&candidate.to) && game.board[&candidate.to].color != game.active_color {
+                WIN_SCORE + game.see(&candidate)
+            } else {
+                0
+            };
+            return (order, candidate);
+        }).collect();
+
+        // Stable, so moves of the same order stay ordered by square
+        scored.sort_by_key(|(order, _)| cmp::Reverse(*order));
+        return scored.into_iter().map(|(_, candidate)| candidate).collect();
+    }
+}
+
+// Every move of the side to move, with promotions to every role the variant allows
+pub(crate) fn legal_moves(game: &Game) -> Vec<Move> {
+    let mut moves: Vec<Move> = Vec::new();
+    let rows: i8 = game.get_dimensions().rows;
+
+    for (from, to) in game.get_legal_moves() {
+        let promotes: bool = game.board[&from].role == Role::Pawn && (to.row == 1 || to.row == rows);
+        if !promotes {
+            moves.push(Move::new(from, to));
+            continue;
+        }
+
+        for role in PROMOTIONS.iter().filter(|role| game.allows_promotion(role)) {
+            moves.push(Move { from: from.clone(), to: to.clone(), promotion: Some(role.clone()) });
+        }
+    }
+
+    return moves;
+}
+
+// The game after a move, with the duck put on the first free square in Duck chess.
+// The move is written down in the history, see `make_move` for the search.
+pub(crate) fn play(game: &Game, _move: &Move) -> Option<Game> {
+    let mut next: Game = game.clone();
+    next.king_danger_assist = false;
+    return finish_move(next, _move);
+}
+
+// Same as `play` on a copy of only the position, without the history or the start
+// position of the game. Moves played on it are not written down, so no notation is
+// worked out either.
+fn make_move(game: &Game, _move: &Move) -> Option<Game> {
+    let next: Game = Game {
+        state: game.state,
+        board: game.board.clone(),
+        active_color: game.active_color.clone(),
+        halfmove_clock: game.halfmove_clock,
+        fullmove_number: game.fullmove_number,
+        history: Vec::new(),
+        record: false,
+        variant: game.variant,
+        chess960: game.chess960,
+        white_checks: game.white_checks,
+        black_checks: game.black_checks,
+        white_pocket: game.white_pocket.clone(),
+        black_pocket: game.black_pocket.clone(),
+        duck: game.duck.clone(),
+        board_hash: game.board_hash,
+        king_danger_assist: false,
+        start_fen: String::new()
+    };
+    return finish_move(next, _move);
+}
+
+fn finish_move(mut next: Game, _move: &Move) -> Option<Game> {
+    next.try_play(_move).ok()?;
+
+    if next.state == GameState::DuckPlacement {
+        let square: String = next.get_duck_squares().first()?.to_string();
+        next.try_place_duck(&square).ok()?;
+    }
+    return Some(next);
+}
+
+// Material of the side to move minus the material of the other side, kings left out
+pub(crate) fn evaluate(game: &Game) -> i32 {
+    let mut score: i32 = 0;
+
+    for piece in game.board.values().filter(|piece| piece.role != Role::King) {
+        if piece.color == game.active_color {
+            score += piece.role.value();
+        } else {
+            score -= piece.role.value();
+        }
+    }
+
+    // Pieces in the pocket count as well
+    for role in game.get_pocket(&game.active_color) {
+        score += role.value();
+    }
+    for role in game.get_pocket(&game.active_color.opponent()) {
+        score -= role.value();
+    }
+
+    return score;
+}
+
+// King captures are stored counted from the position, not from the root
+fn to_table(score: i32, ply: i32) -> i32 {
+    if score > WIN_BOUND {
+        return score + ply;
+    } else if score < -WIN_BOUND {
+        return score - ply;
+    }
+    return score;
+}
+
+fn from_table(score: i32, ply: i32) -> i32 {
+    if score > WIN_BOUND {
+        return score - ply;
+    } else if score < -WIN_BOUND {
+        return score + ply;
+    }
+    return score;
+}
@@ -0,0 +1,208 @@
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use crate::{Move, Position, Role};
+
+/* Transposition table.
+ *
+ * A fixed number of slots, each holding what a search found out about one
+ * position: the depth it searched to, the score, whether the score is
+ * exact or only a bound, and the best move. A position goes to the slot
+ * its hash points at.
+ *
+ * Slots are three atomic words, the hash xor the two data words and the
+ * data words themselves. Threads read and write them without locks, and a
+ * slot torn by two writers at once no longer matches its hash and is read
+ * as empty.
+ *
+ * A new entry replaces the old one in its slot when it is about the same
+ * position, when the old one is left over from an earlier search, or when
+ * it was searched at least as deep. `new_search` starts the next search
+ * and ages all entries at once.
+ */
+
+/// How a stored score relates to the true score of the position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+
+    // The search failed high, the true score is at least this
+    Lower,
+
+    // The search failed low, the true score is at most this
+    Upper
+}
+
+/// What a search stored about a position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub key: u64,
+    pub depth: u8,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<Move>,
+
+    // Search the entry was stored in
+    pub age: u8
+}
+
+/// Fixed-size table of search results keyed by Zobrist hash, shareable between threads.
+pub struct TranspositionTable {
+    slots: Vec<[AtomicU64; 3]>,
+    age: AtomicU8
+}
+
+// Bytes of a slot
+const SLOT_SIZE: usize = 24;
+
+impl TranspositionTable {
+
+    /// Create a table taking about the given number of megabytes, at least one slot.
+    pub fn new(megabytes: usize) -> TranspositionTable {
+        let count: usize = std::cmp::max(megabytes * 1024 * 1024 / SLOT_SIZE, 1);
+        let slots: Vec<[AtomicU64; 3]> = (0..count).map(|_| [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)]).collect();
+
+        return TranspositionTable { slots, age: AtomicU8::new(0) };
+    }
+
+    /// Get the number of slots.
+    pub fn capacity(&self) -> usize {
+        return self.slots.len();
+    }
+
+    /// Forget every entry.
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter() {
+            for word in slot.iter() {
+                word.store(0, Ordering::Relaxed);
+            }
+        }
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    /// Start a new search, entries stored so far are replaced first from now on.
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the entry stored for a position.
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        let entry: Entry = self.read(self.index(key))?;
+        if entry.key != key {
+            return None;
+        }
+        return Some(entry);
+    }
+
+    /// Store what a search found out about a position, if it is worth more than what the slot holds.
+    pub fn store(&self, key: u64, depth: u8, score: i32, bound: Bound, best_move: Option<Move>) {
+        let index: usize = self.index(key);
+        let age: u8 = self.age.load(Ordering::Relaxed);
+        let mut best_move: Option<Move> = best_move;
+
+        if let Some(old) = self.read(index) {
+            let replace: bool = old.key == key || old.age != age || depth >= old.depth;
+            if !replace {
+                return;
+            }
+
+            // Keep the best move of the same position when a bound comes without one
+            if old.key == key && best_move.is_none() {
+                best_move = old.best_move;
+            }
+        }
+
+        self.write(index, Entry { key, depth, score, bound, best_move, age });
+    }
+
+    /// Get how full the table is in permille, counting entries of the current
+    /// search in the first thousand slots.
+    pub fn hashfull(&self) -> usize {
+        let sample: usize = std::cmp::min(1000, self.slots.len());
+        let age: u8 = self.age.load(Ordering::Relaxed);
+        let used: usize = (0..sample).filter(|index| match self.read(*index) {
+            Some(entry) => entry.age == age,
+            None => false
+        }).count();
+
+        return used * 1000 / sample;
+    }
+
+    fn index(&self, key: u64) -> usize {
+        return (key % self.slots.len() as u64) as usize;
+    }
+
+    // An empty or torn slot reads as nothing
+    fn read(&self, index: usize) -> Option<Entry> {
+        let slot: &[AtomicU64; 3] = &self.slots[index];
+        let data: u64 = slot[1].load(Ordering::Relaxed);
+        let best: u64 = slot[2].load(Ordering::Relaxed);
+        let key: u64 = slot[0].load(Ordering::Relaxed) ^ data ^ best;
+
+        if data == 0 {
+            return None;
+        }
+
+        let bound: Bound = match (data >> 40) & 0xff {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None
+        };
+
+        return Some(Entry {
+            key,
+            depth: (data >> 32) as u8,
+            score: data as u32 as i32,
+            bound,
+            best_move: unpack_move(best),
+            age: (data >> 48) as u8
+        });
+    }
+
+    fn write(&self, index: usize, entry: Entry) {
+        let bound: u64 = match entry.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3
+        };
+        let data: u64 = (entry.age as u64) << 48 | bound << 40 | (entry.depth as u64) << 32 | entry.score as u32 as u64;
+        let best: u64 = pack_move(&entry.best_move);
+
+        let slot: &[AtomicU64; 3] = &self.slots[index];
+        slot[0].store(entry.key ^ data ^ best, Ordering::Relaxed);
+        slot[1].store(data, Ordering::Relaxed);
+        slot[2].store(best, Ordering::Relaxed);
+    }
+}
+
+// A move as squares and promotion in one byte each, 0 for no move
+fn pack_move(_move: &Option<Move>) -> u64 {
+    let best: &Move = match _move {
+        Some(best) => best,
+        None => return 0
+    };
+
+    let promotion: u64 = match &best.promotion {
+        Some(role) => role.to_char() as u64,
+        None => 0
+    };
+    return 1 << 40 | promotion << 32
+        | (best.from.row as u8 as u64) << 24 | (best.from.column as u8 as u64) << 16
+        | (best.to.row as u8 as u64) << 8 | best.to.column as u8 as u64;
+}
+
+fn unpack_move(best: u64) -> Option<Move> {
+    if best == 0 {
+        return None;
+    }
+
+    let promotion: Option<Role> = match (best >> 32) as u8 {
+        0 => None,
+        letter => Role::from_char(letter as char)
+    };
+    return Some(Move {
+        from: Position { row: (best >> 24) as u8 as i8, column: (best >> 16) as u8 as i8 },
+        to: Position { row: (best >> 8) as u8 as i8, column: best as u8 as i8 },
+        promotion
+    });
+}
@@ -0,0 +1,280 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::analysis::Line;
+use super::limits::{Limits, SearchControl};
+use super::search::{legal_moves, play, Engine};
+use crate::{Color, Game, Move};
+
+/* Universal Chess Interface.
+ *
+ * `Uci` reads the commands of a chess GUI one line at a time and writes
+ * the answers to any writer. It knows the commands a GUI needs to run
+ * analysis: uci, isready, setoption, ucinewgame, position, go, stop and
+ * quit. Searches run on their own thread, so stop is heard while one is
+ * running.
+ *
+ * The options are Threads, the number of search threads, Hash, the size
+ * of the transposition table in megabytes, and MultiPV, the number of
+ * best moves reported. They wait for a running search to stop before they
+ * are changed.
+ *
+ * Castling goes out the way GUIs expect it, as the king moving to its
+ * castling square, e.g. e1g1. With UCI_Chess960 set, and in games started
+ * from a Chess960 position, it is the king moving onto its rook instead.
+ *
+ * King captures are reported as mate scores, "mate 2" is a king capture
+ * with the second move of the engine.
+ */
+
+// Writer shared with the search thread
+type Output = Arc<Mutex<Box<dyn Write + Send>>>;
+
+/// Engine speaking the Universal Chess Interface.
+pub struct Uci {
+    engine: Arc<Engine>,
+    game: Game,
+    output: Output,
+    multipv: usize,
+
+    // Castling written as the king moving onto its rook
+    chess960: bool,
+
+    // Stop flag and thread of the running search
+    search: Option<(Arc<AtomicBool>, JoinHandle<()>)>
+}
+
+// Part of the remaining time a move may take
+const TIME_SHARE: u32 = 30;
+
+impl Uci {
+
+    /// Create an interface writing its answers to the given writer.
+    pub fn new(output: Box<dyn Write + Send>) -> Uci {
+        return Uci { engine: Arc::new(Engine::new()), game: Game::new(), output: Arc::new(Mutex::new(output)), multipv: 1, chess960: false, search: None };
+    }
+
+    /// Get the engine.
+    pub fn get_engine(&self) -> &Engine {
+        return &self.engine;
+    }
+
+    /// Handle one line from the GUI. Returns false on quit.
+    pub fn handle(&mut self, line: &str) -> bool {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.first() {
+            Some(&"uci") => {
+                self.write("id name Rasan Chess");
+                self.write("id author Rasmus Andersson");
+                self.write("option name Threads type spin default 1 min 1 max 256");
+                self.write("option name Hash type spin default 16 min 1 max 4096");
+                self.write("option name MultiPV type spin default 1 min 1 max 256");
+                self.write("option name UCI_Chess960 type check default false");
+                self.write("uciok");
+            },
+            Some(&"isready") => self.write("readyok"),
+            Some(&"setoption") => self.set_option(&words[1..]),
+            Some(&"ucinewgame") => {
+                self.stop();
+                self.game = Game::new();
+                if let Some(engine) = Arc::get_mut(&mut self.engine) {
+                    engine.new_game();
+                }
+            },
+            Some(&"position") => {
+                self.stop();
+                self.set_position(&words[1..]);
+            },
+            Some(&"go") => {
+                self.stop();
+                self.go(&words[1..]);
+            },
+            Some(&"stop") => self.stop(),
+            Some(&"quit") => {
+                self.stop();
+                return false;
+            },
+            _ => ()
+        }
+
+        return true;
+    }
+
+    /// Stop the running search and wait until it has told its best move.
+    pub fn stop(&mut self) {
+        if let Some((flag, handle)) = self.search.take() {
+            flag.store(true, Ordering::Relaxed);
+            handle.join().ok();
+        }
+    }
+
+    // setoption name <name> value <value>
+    fn set_option(&mut self, words: &[&str]) {
+        let name: Option<String> = words.iter().position(|word| *word == "name")
+            .and_then(|index| words.get(index + 1))
+            .map(|name| name.to_ascii_lowercase());
+        let value: Option<&str> = words.iter().position(|word| *word == "value").and_then(|index| words.get(index + 1)).copied();
+
+        // The engine is only shared while a search runs
+        self.stop();
+        if name.as_deref() == Some("uci_chess960") {
+            self.chess960 = value == Some("true");
+            return;
+        }
+
+        let value: usize = match value.and_then(|value| value.parse().ok()) {
+            Some(value) => value,
+            None => return
+        };
+        if name.as_deref() == Some("multipv") {
+            self.multipv = std::cmp::max(value, 1);
+            return;
+        }
+        let engine: &mut Engine = match Arc::get_mut(&mut self.engine) {
+            Some(engine) => engine,
+            None => return
+        };
+
+        match name.as_deref() {
+            Some("threads") => engine.set_threads(value),
+            Some("hash") => engine.set_hash(value),
+            _ => ()
+        }
+    }
+
+    // position (startpos | fen <fen>) [moves <move> ...]
+    fn set_position(&mut self, words: &[&str]) {
+        let moves: usize = words.iter().position(|word| *word == "moves").unwrap_or(words.len());
+
+        let game: Option<Game> = match words.first() {
+            Some(&"startpos") => Some(Game::new()),
+            Some(&"fen") => Game::from_fen(&words[1..moves].join(" ")).ok(),
+            _ => None
+        };
+        let mut game: Game = match game {
+            Some(game) => game,
+            None => return
+        };
+
+        // Moves which are not legal end the list
+        for word in words.iter().skip(moves + 1) {
+            let played: bool = match Move::parse(word) {
+                Some(_move) => game.try_play(&_move).is_ok(),
+                None => false
+            };
+            if !played {
+                break;
+            }
+        }
+
+        self.game = game;
+    }
+
+    // go [depth <plies>] [nodes <nodes>] [movetime <ms>] [wtime <ms>] [btime <ms>] [infinite]
+    fn go(&mut self, words: &[&str]) {
+        let number = |name: &str| -> Option<u64> {
+            let index: usize = words.iter().position(|word| *word == name)?;
+            return words.get(index + 1)?.parse().ok();
+        };
+
+        let mut limits: Limits = Limits {
+            depth: number("depth").map(|depth| depth.min(u8::MAX as u64) as u8),
+            nodes: number("nodes"),
+            time: number("movetime").map(Duration::from_millis)
+        };
+
+        // A share of the clock when no time is given
+        let clock: Option<u64> = match self.game.active_color {
+            Color::White => number("wtime"),
+            Color::Black => number("btime")
+        };
+        if limits.time.is_none() && !words.contains(&"infinite") {
+            if let Some(clock) = clock {
+                limits.time = Some(Duration::from_millis(clock) / TIME_SHARE);
+            }
+        }
+
+        let control: SearchControl = SearchControl::new(limits);
+        let flag: Arc<AtomicBool> = control.stop_flag();
+        let engine: Arc<Engine> = self.engine.clone();
+        let game: Game = self.game.clone();
+        let output: Output = self.output.clone();
+        let multipv: usize = self.multipv;
+        let chess960: bool = self.chess960 || self.game.chess960;
+
+        let handle: JoinHandle<()> = thread::spawn(move || {
+            let lines: Vec<Line> = engine.analyse_with(&game, &control, multipv, &mut |lines| {
+                for (index, line) in lines.iter().enumerate() {
+                    let info: String = info(index + 1, line, &notation(&game, &line.pv, chess960), &control, &engine);
+                    write_line(&output, &info);
+                }
+            });
+
+            // Stopped before the first depth was done, any move is better than none
+            let best_move: Option<Move> = match lines.first() {
+                Some(line) => Some(line.get_move().clone()),
+                None => legal_moves(&game).into_iter().next()
+            };
+            let best_move: String = match best_move {
+                Some(best_move) => notation(&game, &[best_move], chess960).remove(0),
+                None => String::from("0000")
+            };
+            write_line(&output, &format!("bestmove {}", best_move));
+        });
+
+        self.search = Some((flag, handle));
+    }
+
+    fn write(&self, line: &str) {
+        write_line(&self.output, line);
+    }
+}
+
+impl Drop for Uci {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Info line for one line of a finished depth
+fn info(multipv: usize, line: &Line, pv: &[String], control: &SearchControl, engine: &Engine) -> String {
+    let millis: u128 = control.elapsed().as_millis();
+    let nodes: u64 = control.nodes();
+    let nps: u128 = nodes as u128 * 1000 / std::cmp::max(millis, 1);
+
+    return format!("info depth {} multipv {} score {} nodes {} nps {} time {} hashfull {} pv {}",
+        line.depth, multipv, line.score.to_uci(), nodes, nps, millis, engine.get_table().hashfull(), pv.join(" "));
+}
+
+// Moves played one after the other in UCI notation, castling as the king moving
+// to its castling square unless written the Chess960 way
+fn notation(game: &Game, moves: &[Move], chess960: bool) -> Vec<String> {
+    let mut notation: Vec<String> = Vec::new();
+    let mut position: Game = game.clone();
+
+    for _move in moves {
+        let mut written: Move = _move.clone();
+        if !chess960 && position.is_castling(&_move.from, &_move.to) {
+            written.to = position.castling_square(&_move.from, &_move.to);
+        }
+        notation.push(written.to_string());
+
+        position = match play(&position, _move) {
+            Some(next) => next,
+            None => break
+        };
+    }
+
+    return notation;
+}
+
+fn write_line(output: &Output, line: &str) {
+    if let Ok(mut output) = output.lock() {
+        writeln!(output, "{}", line).ok();
+        output.flush().ok();
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::{Color, Game, GameState, Piece, Position, Role};
+
+/* Zobrist hashing.
+ *
+ * Every feature of a position, e.g. a white knight on f3, has a random
+ * 64 bit key, and the hash of a position is all its keys combined with
+ * xor. The keys come from a fixed seed, so hashes stay the same between
+ * runs and builds.
+ *
+ * The pieces make up most of the keys. Their part of the hash is kept on
+ * the game and updated as pieces are put on and taken off the board, so
+ * a move only touches the keys of the pieces it moves. The few other
+ * features are added when the hash is asked for.
+ */
+
+// Kinds of features, to keep their keys apart
+const PIECE: u64 = 1;
+const UNMOVED: u64 = 2;
+const BLACK_TO_MOVE: u64 = 3;
+const POCKET: u64 = 4;
+const DUCK: u64 = 5;
+const CHECKS: u64 = 6;
+const STATE: u64 = 7;
+const VARIANT: u64 = 8;
+
+impl Game {
+
+    /// Get the Zobrist hash of the position. Positions with the same pieces,
+    /// side to move, castling rights, pockets, duck, checks and pending
+    /// promotion share a hash, whatever the move counters say.
+    pub fn zobrist_hash(&self) -> u64 {
+        debug_assert_eq!(self.board_hash, board_hash(&self.board));
+        let mut hash: u64 = self.board_hash ^ key(VARIANT, self.variant as u64, 0, 0);
+
+        if self.active_color == Color::Black {
+            hash ^= key(BLACK_TO_MOVE, 0, 0, 0);
+        }
+
+        for color in [Color::White, Color::Black].iter() {
+            let pocket: Vec<Role> = self.get_pocket(color);
+
+            // The first, second, ... piece of a role each have their own key
+            for (index, role) in pocket.iter().enumerate() {
+                let earlier: usize = pocket[..index].iter().filter(|other| *other == role).count();
+                hash ^= key(POCKET, color_index(color) * 16 + role.index() as u64, earlier as u64, 0);
+            }
+        }
+
+        if let Some(duck) = &self.duck {
+            hash ^= key(DUCK, square_index(duck.row, duck.column), 0, 0);
+        }
+
+        hash ^= key(CHECKS, self.white_checks as u64, self.black_checks as u64, 0);
+
+        match self.state {
+            GameState::Promotion => hash ^= key(STATE, 1, 0, 0),
+            GameState::DuckPlacement => hash ^= key(STATE, 2, 0, 0),
+            _ => ()
+        }
+
+        return hash;
+    }
+
+    // Put a piece on its square, returning the piece which stood there
+    pub(crate) fn put_piece(&mut self, piece: Piece) -> Option<Piece> {
+        self.board_hash ^= piece_key(&piece);
+        let replaced: Option<Piece> = self.board.insert(piece.position.clone(), piece);
+        if let Some(replaced) = &replaced {
+            self.board_hash ^= piece_key(replaced);
+        }
+        return replaced;
+    }
+
+    // Take the piece on the square off the board
+    pub(crate) fn take_piece(&mut self, _position: &Position) -> Option<Piece> {
+        match self.board.remove(_position) {
+            Some(piece) => {
+                self.board_hash ^= piece_key(&piece);
+                return Some(piece);
+            },
+            None => return None
+        }
+    }
+}
+
+// Keys of all pieces on a board combined
+pub(crate) fn board_hash(board: &HashMap<Position, Piece>) -> u64 {
+    return board.values().fold(0, |hash, piece| hash ^ piece_key(piece));
+}
+
+// Key of a piece on its square
+fn piece_key(piece: &Piece) -> u64 {
+    let square: u64 = square_index(piece.position.row, piece.position.column);
+    let mut hash: u64 = key(PIECE, color_index(&piece.color) * 16 + piece.role.index() as u64, square, piece.promoted as u64);
+
+    // Kings and rooks which have not moved may castle
+    if !piece.has_moved && (piece.role == Role::King || piece.role == Role::Rook) {
+        hash ^= key(UNMOVED, square, 0, 0);
+    }
+    return hash;
+}
+
+// Random key of a feature, splitmix64 of its description
+fn key(kind: u64, a: u64, b: u64, c: u64) -> u64 {
+    let mut z: u64 = (kind << 56 ^ a << 32 ^ b << 16 ^ c).wrapping_mul(0x9e37_79b9_7f4a_7c15).wrapping_add(0x2545_f491_4f6c_dd1d);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    return z ^ (z >> 31);
+}
+
+fn square_index(row: i8, column: i8) -> u64 {
+    return (row as u64) << 8 | column as u64;
+}
+
+fn color_index(_color: &Color) -> u64 {
+    match _color {
+        Color::White => return 0,
+        Color::Black => return 1
+    }
+}
+
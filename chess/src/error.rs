@@ -0,0 +1,32 @@
+use std::fmt;
+
+/// Why a move or promotion was rejected.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveError {
+    InvalidPosition,
+    NoPiece,
+    NotYourTurn,
+    IllegalMove,
+    GameOver,
+    PromotionPending,
+    PromotionNotAllowed,
+    DuckPending,
+    SuicidalMove
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message: &str = match self {
+            MoveError::InvalidPosition => "Not a valid position",
+            MoveError::NoPiece => "No piece at specified position",
+            MoveError::NotYourTurn => "Not your turn",
+            MoveError::IllegalMove => "Illegal move",
+            MoveError::GameOver => "Game is over",
+            MoveError::PromotionPending => "Waiting for promotion",
+            MoveError::PromotionNotAllowed => "Promotion not allowed",
+            MoveError::DuckPending => "Waiting for the duck",
+            MoveError::SuicidalMove => "Move leaves the king capturable, confirm it to play it"
+        };
+        write!(f, "{}", message)
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::castling::apply_castling_field;
+use crate::variant::CHECKS_TO_WIN;
+use crate::{Color, Dimensions, Game, Piece, Position, Role, Variant};
+
+/* Forsyth-Edwards Notation.
+ *
+ * Castling rights are read as X-FEN or Shredder-FEN, so Chess960 positions
+ * round-trip. Chess royale has no en passant, that field is always "-".
+ *
+ * Three-check adds the checks each side has left after the en passant
+ * field, e.g. "3+3". The older form with the checks given at the very
+ * end, e.g. "+0+0", is read as well.
+ *
+ * Crazyhouse adds the pockets in brackets after the placement, e.g.
+ * "RNBQKBNR[Qn]", and marks promoted pieces with "~".
+ *
+ * Duck chess marks the duck with "*" in the placement.
+ *
+ * Boards wider than nine files count empty squares with more than one
+ * digit, e.g. "10" for an empty rank in Capablanca chess.
+ */
+
+// White and black pocket in Crazyhouse
+type Pockets = (Vec<Role>, Vec<Role>);
+
+/// Why a FEN string could not be read.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FenError {
+    MissingField,
+    InvalidPlacement,
+    InvalidColor,
+    InvalidCastling,
+    InvalidEnPassant,
+    InvalidCounter,
+    InvalidChecks,
+    InvalidPocket
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message: &str = match self {
+            FenError::MissingField => "FEN is missing fields",
+            FenError::InvalidPlacement => "Invalid piece placement",
+            FenError::InvalidColor => "Invalid active color",
+            FenError::InvalidCastling => "Invalid castling field",
+            FenError::InvalidEnPassant => "Invalid en passant field",
+            FenError::InvalidCounter => "Invalid move counter",
+            FenError::InvalidChecks => "Invalid check counter",
+            FenError::InvalidPocket => "Invalid pocket"
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Game {
+
+    /// Set up a game from Forsyth-Edwards Notation. The move counters may be left out.
+    /// Check counters make it a game of Three-check, pockets a game of Crazyhouse
+    /// and a duck a game of Duck chess.
+    pub fn from_fen(fen: &str) -> Result<Game, FenError> {
+        return Self::from_fen_variant(fen, Variant::Standard);
+    }
+
+    /// Same as `from_fen`, for a game played by the given variant.
+    pub fn from_fen_variant(fen: &str, variant: Variant) -> Result<Game, FenError> {
+        let mut fields: Vec<&str> = fen.split_whitespace().collect();
+
+        // Take out the Three-check counters, wherever they are
+        let mut checks: Option<(u32, u32)> = None;
+        if let Some(index) = fields.iter().position(|field| field.contains('+')) {
+            checks = Some(parse_checks(fields.remove(index))?);
+        }
+
+        if fields.len() < 4 {
+            return Err(FenError::MissingField);
+        }
+
+        // Crazyhouse pockets follow the placement in brackets
+        let (placement, pockets): (&str, Option<Pockets>) = match fields[0].find('[') {
+            Some(index) => (&fields[0][..index], Some(parse_pockets(&fields[0][index..])?)),
+            None => (fields[0], None)
+        };
+
+        let dimensions: Dimensions = variant.dimensions();
+        let (mut board, duck): (HashMap<Position, Piece>, Option<Position>) = parse_placement(placement, &dimensions)?;
+
+        let active_color: Color = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(FenError::InvalidColor)
+        };
+
+        // Castling rights end up in the has_moved flags of kings and rooks
+        let chess960: bool = apply_castling_field(&mut board, &dimensions, fields[2])?;
+
+        // There is no en passant in Chess royale, but accept the field from other tools
+        if fields[3] != "-" && Position::parse(fields[3]).is_none() {
+            return Err(FenError::InvalidEnPassant);
+        }
+
+        // Move counters
+        let halfmove_clock: u32 = match fields.get(4) {
+            Some(field) => field.parse().map_err(|_| FenError::InvalidCounter)?,
+            None => 0
+        };
+        let fullmove_number: u32 = match fields.get(5) {
+            Some(field) => field.parse().map_err(|_| FenError::InvalidCounter)?,
+            None => 1
+        };
+        if fullmove_number == 0 {
+            return Err(FenError::InvalidCounter);
+        }
+
+        let mut game: Game = Game::with_board(board, active_color, halfmove_clock, fullmove_number, variant, chess960);
+        if let Some((white_checks, black_checks)) = checks {
+            if variant == Variant::Standard {
+                game.variant = Variant::ThreeCheck;
+            }
+            game.white_checks = white_checks;
+            game.black_checks = black_checks;
+        }
+        if let Some((white_pocket, black_pocket)) = pockets {
+            if game.variant == Variant::Standard {
+                game.variant = Variant::Crazyhouse;
+            }
+            game.white_pocket = white_pocket;
+            game.black_pocket = black_pocket;
+        }
+        if duck.is_some() {
+            if game.variant == Variant::Standard {
+                game.variant = Variant::Duck;
+            }
+            game.duck = duck;
+        }
+
+        // The variant may have changed
+        game.start_fen = game.to_fen();
+        return Ok(game);
+    }
+
+    /// Describe the current position in Forsyth-Edwards Notation. Castling
+    /// rights are written as X-FEN, which is plain FEN for standard games.
+    pub fn to_fen(&self) -> String {
+        return self.fen(false);
+    }
+
+    /// Same as `to_fen`, but castling rights name the files of the rooks.
+    pub fn to_shredder_fen(&self) -> String {
+        return self.fen(true);
+    }
+
+    fn fen(&self, shredder: bool) -> String {
+        let mut fen: String = String::new();
+
+        // Piece placement, from the last rank down to rank 1
+        let dimensions: Dimensions = self.get_dimensions();
+        for row in (1..=dimensions.rows).rev() {
+            let mut empty: u8 = 0;
+
+            for column in 1..=dimensions.columns {
+                let position: Position = Position { row, column };
+
+                // The duck stands where no piece is
+                if self.duck.as_ref() == Some(&position) {
+                    if empty > 0 {
+                        fen.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    fen.push('*');
+                    continue;
+                }
+
+                match self.board.get(&position) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+
+                        // White pieces are upper case, black lower case
+                        let letter: char = piece.role.to_char();
+                        match piece.color {
+                            Color::White => fen.push(letter),
+                            Color::Black => fen.push(letter.to_ascii_lowercase())
+                        }
+
+                        // Crazyhouse marks pieces which are pawns once captured
+                        if piece.promoted && self.has_pockets() {
+                            fen.push('~');
+                        }
+                    },
+                    None => empty += 1
+                }
+            }
+
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if row > 1 {
+                fen.push('/');
+            }
+        }
+
+        // Pockets in Crazyhouse and Bughouse
+        if self.has_pockets() {
+            fen.push_str(&self.pocket_field());
+        }
+
+        // Active color
+        match self.active_color {
+            Color::White => fen.push_str(" w"),
+            Color::Black => fen.push_str(" b")
+        }
+
+        // Castling and en passant
+        fen.push_str(&format!(" {} -", self.castling_field(shredder)));
+
+        // Checks left in Three-check
+        if self.variant == Variant::ThreeCheck {
+            let left = |checks: u32| CHECKS_TO_WIN.saturating_sub(checks);
+            fen.push_str(&format!(" {}+{}", left(self.white_checks), left(self.black_checks)));
+        }
+
+        // Move counters
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
+
+        return fen;
+    }
+}
+
+// Read the piece placement field, last rank first, and the square of the duck
+fn parse_placement(placement: &str, dimensions: &Dimensions) -> Result<(HashMap<Position, Piece>, Option<Position>), FenError> {
+    let mut board: HashMap<Position, Piece> = HashMap::new();
+    let mut duck: Option<Position> = None;
+
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != dimensions.rows as usize {
+        return Err(FenError::InvalidPlacement);
+    }
+
+    for (index, rank) in ranks.iter().enumerate() {
+        let row: i8 = dimensions.rows - index as i8;
+        let mut column: i8 = 1;
+
+        // Digits skip empty squares, a number may have more than one digit
+        let mut empty: u32 = 0;
+
+        for letter in rank.chars() {
+            if let Some(digit) = letter.to_digit(10) {
+                if empty == 0 && digit == 0 {
+                    return Err(FenError::InvalidPlacement);
+                }
+                empty = empty * 10 + digit;
+                if empty > dimensions.columns as u32 {
+                    return Err(FenError::InvalidPlacement);
+                }
+                continue;
+            }
+            column += empty as i8;
+            empty = 0;
+
+            // A promoted piece is marked right after its letter
+            if letter == '~' {
+                match board.get_mut(&Position { row, column: column - 1 }) {
+                    Some(piece) => piece.promoted = true,
+                    None => return Err(FenError::InvalidPlacement)
+                }
+                continue;
+            }
+
+            if column > dimensions.columns {
+                return Err(FenError::InvalidPlacement);
+            }
+
+            // There is only one duck
+            if letter == '*' {
+                if duck.is_some() {
+                    return Err(FenError::InvalidPlacement);
+                }
+                duck = Some(Position { row, column });
+                column += 1;
+                continue;
+            }
+
+            let role: Role = Role::from_char(letter).ok_or(FenError::InvalidPlacement)?;
+            let color: Color = if letter.is_ascii_uppercase() { Color::White } else { Color::Black };
+            let position: Position = Position { row, column };
+
+            board.insert(position.clone(), placed_piece(color, role, position));
+            column += 1;
+        }
+
+        if column + empty as i8 != dimensions.columns + 1 {
+            return Err(FenError::InvalidPlacement);
+        }
+    }
+
+    return Ok((board, duck));
+}
+
+// Read Crazyhouse pockets, "[Qn]", as the white and the black pocket
+fn parse_pockets(field: &str) -> Result<Pockets, FenError> {
+    if !field.starts_with('[') || !field.ends_with(']') {
+        return Err(FenError::InvalidPocket);
+    }
+
+    let mut white: Vec<Role> = Vec::new();
+    let mut black: Vec<Role> = Vec::new();
+    for letter in field[1..field.len() - 1].chars() {
+        match Role::from_char(letter) {
+            Some(Role::King) | None => return Err(FenError::InvalidPocket),
+            Some(role) => {
+                if letter.is_ascii_uppercase() {
+                    white.push(role);
+                } else {
+                    black.push(role);
+                }
+            }
+        }
+    }
+
+    return Ok((white, black));
+}
+
+// Read Three-check counters as the checks given by white and black
+fn parse_checks(field: &str) -> Result<(u32, u32), FenError> {
+    let counters: Vec<&str> = field.split('+').collect();
+
+    let parse = |counter: &str| -> Result<u32, FenError> {
+        match counter.parse::<u32>() {
+            Ok(checks) if checks <= CHECKS_TO_WIN => return Ok(checks),
+            _ => return Err(FenError::InvalidChecks)
+        }
+    };
+
+    match counters.as_slice() {
+
+        // Checks given, "+0+0"
+        ["", white, black] => return Ok((parse(white)?, parse(black)?)),
+
+        // Checks left, "3+3"
+        [white, black] => return Ok((CHECKS_TO_WIN - parse(white)?, CHECKS_TO_WIN - parse(black)?)),
+        _ => return Err(FenError::InvalidChecks)
+    }
+}
+
+// A piece put on the board outside of a game
+pub(crate) fn placed_piece(color: Color, role: Role, position: Position) -> Piece {
+
+    // Pawns off their starting rank have moved
+    let has_moved: bool = match (&role, &color) {
+        (Role::Pawn, Color::White) => position.row != 2,
+        (Role::Pawn, Color::Black) => position.row != 7,
+        _ => false
+    };
+
+    return Piece { color, role, position, has_moved, promoted: false };
+}
@@ -0,0 +1,421 @@
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::{Color, FenError, Game, GameState, MoveError, Position, Role, ROLES};
+
+/* C interface, enabled with the "ffi" feature. The header is generated
+ * into include/rasan_chess.h by the build script.
+ *
+ * Ownership:
+ * - Games are opaque handles. chess_game_new and chess_game_from_fen hand
+ *   the game to the caller, who must release it with chess_game_free.
+ * - Strings passed in are borrowed for the duration of the call and must
+ *   be NUL terminated.
+ * - Output buffers belong to the caller. Functions never allocate memory
+ *   the caller has to free, except for game handles.
+ *
+ * Every function except chess_game_new and chess_game_free returns a
+ * ChessStatus, CHESS_STATUS_OK on success. Nothing is printed.
+ *
+ * Roles and colors are passed in as plain integers holding a ChessRole or
+ * ChessColor, since any value can arrive from C. Values outside the enum
+ * are refused with CHESS_STATUS_INVALID_ARGUMENT.
+ */
+
+/// Result code of every fallible call.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChessStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidString,
+    InvalidPosition,
+    NoPiece,
+    NotYourTurn,
+    IllegalMove,
+    GameOver,
+    PromotionPending,
+    PromotionNotAllowed,
+    InvalidFen,
+    BufferTooSmall,
+    DuckPending,
+    SuicidalMove,
+    InvalidArgument
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChessGameState {
+    InProgress = 0,
+    Promotion,
+    Check,
+    GameOver,
+    DuckPlacement
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChessColor {
+    White = 0,
+    Black
+}
+
+/// Roles in the order of `ROLES`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ChessRole {
+    King = 0,
+    Queen,
+    Rook,
+    Bishop,
+    Knight,
+    Pawn,
+    Archbishop,
+    Chancellor,
+    Amazon,
+    Nightrider,
+    Grasshopper
+}
+
+/// A square, column 1 is the a-file and row 1 is white's back rank.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChessSquare {
+    pub column: i8,
+    pub row: i8
+}
+
+impl From<MoveError> for ChessStatus {
+    fn from(error: MoveError) -> Self {
+        match error {
+            MoveError::InvalidPosition => ChessStatus::InvalidPosition,
+            MoveError::NoPiece => ChessStatus::NoPiece,
+            MoveError::NotYourTurn => ChessStatus::NotYourTurn,
+            MoveError::IllegalMove => ChessStatus::IllegalMove,
+            MoveError::GameOver => ChessStatus::GameOver,
+            MoveError::PromotionPending => ChessStatus::PromotionPending,
+            MoveError::PromotionNotAllowed => ChessStatus::PromotionNotAllowed,
+            MoveError::DuckPending => ChessStatus::DuckPending,
+            MoveError::SuicidalMove => ChessStatus::SuicidalMove
+        }
+    }
+}
+
+impl From<FenError> for ChessStatus {
+    fn from(_: FenError) -> Self {
+        return ChessStatus::InvalidFen;
+    }
+}
+
+impl From<GameState> for ChessGameState {
+    fn from(state: GameState) -> Self {
+        match state {
+            GameState::InProgress => ChessGameState::InProgress,
+            GameState::Promotion => ChessGameState::Promotion,
+            GameState::Check => ChessGameState::Check,
+            GameState::GameOver => ChessGameState::GameOver,
+            GameState::DuckPlacement => ChessGameState::DuckPlacement
+        }
+    }
+}
+
+// Role of a ChessRole value
+fn read_role(role: u32) -> Result<Role, ChessStatus> {
+    match ROLES.get(role as usize) {
+        Some(info) => return Ok(info.role.clone()),
+        None => return Err(ChessStatus::InvalidArgument)
+    }
+}
+
+// Color of a ChessColor value
+fn read_color(color: u32) -> Result<Color, ChessStatus> {
+    match color {
+        0 => return Ok(Color::White),
+        1 => return Ok(Color::Black),
+        _ => return Err(ChessStatus::InvalidArgument)
+    }
+}
+
+/// Start a game from the initial position. Free it with `chess_game_free`.
+#[no_mangle]
+pub extern "C" fn chess_game_new() -> *mut Game {
+    return Box::into_raw(Box::new(Game::new()));
+}
+
+/// Set up a game from FEN and store the new handle in `out`.
+///
+/// # Safety
+/// `fen` must be a NUL terminated string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_from_fen(fen: *const c_char, out: *mut *mut Game) -> ChessStatus {
+    if out.is_null() {
+        return ChessStatus::NullPointer;
+    }
+    let fen: &str = match read_str(fen) {
+        Ok(fen) => fen,
+        Err(status) => return status
+    };
+
+    match Game::from_fen(fen) {
+        Ok(game) => {
+            *out = Box::into_raw(Box::new(game));
+            return ChessStatus::Ok;
+        },
+        Err(error) => return error.into()
+    }
+}
+
+/// Release a game. Passing NULL does nothing.
+///
+/// # Safety
+/// `game` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_free(game: *mut Game) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Move a piece, e.g. from "e2" to "e4".
+///
+/// # Safety
+/// `game` must be a live handle, `from` and `to` NUL terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn chess_make_move(game: *mut Game, from: *const c_char, to: *const c_char) -> ChessStatus {
+    let game: &mut Game = match game.as_mut() {
+        Some(game) => game,
+        None => return ChessStatus::NullPointer
+    };
+    let (from, to) = match (read_str(from), read_str(to)) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(status), _) | (_, Err(status)) => return status
+    };
+
+    match game.try_make_move(from, to) {
+        Ok(_) => return ChessStatus::Ok,
+        Err(error) => return error.into()
+    }
+}
+
+/// Promote the pawn on `square` after it reached the last rank. `role` is a `ChessRole`.
+///
+/// # Safety
+/// `game` must be a live handle and `square` a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn chess_set_promotion(game: *mut Game, square: *const c_char, role: u32) -> ChessStatus {
+    let game: &mut Game = match game.as_mut() {
+        Some(game) => game,
+        None => return ChessStatus::NullPointer
+    };
+    let square: &str = match read_str(square) {
+        Ok(square) => square,
+        Err(status) => return status
+    };
+
+    let role: Role = match read_role(role) {
+        Ok(role) => role,
+        Err(status) => return status
+    };
+
+    match game.try_set_promotion(square, role) {
+        Ok(_) => return ChessStatus::Ok,
+        Err(error) => return error.into()
+    }
+}
+
+/// Store the current game state in `out`.
+///
+/// # Safety
+/// `game` must be a live handle and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn chess_game_state(game: *const Game, out: *mut ChessGameState) -> ChessStatus {
+    match (game.as_ref(), out.is_null()) {
+        (Some(game), false) => {
+            *out = game.get_game_state().into();
+            return ChessStatus::Ok;
+        },
+        _ => return ChessStatus::NullPointer
+    }
+}
+
+/// Store how many times `color`, a `ChessColor`, has given check in `out`, counted in Three-check.
+///
+/// # Safety
+/// `game` must be a live handle and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn chess_checks(game: *const Game, color: u32, out: *mut u32) -> ChessStatus {
+    match (game.as_ref(), out.is_null()) {
+        (Some(game), false) => {
+            match read_color(color) {
+                Ok(color) => *out = game.get_checks(&color),
+                Err(status) => return status
+            }
+            return ChessStatus::Ok;
+        },
+        _ => return ChessStatus::NullPointer
+    }
+}
+
+/// Store the color whose turn it is in `out`.
+///
+/// # Safety
+/// `game` must be a live handle and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn chess_active_color(game: *const Game, out: *mut ChessColor) -> ChessStatus {
+    match (game.as_ref(), out.is_null()) {
+        (Some(game), false) => {
+            *out = match game.get_active_color() {
+                Color::White => ChessColor::White,
+                Color::Black => ChessColor::Black
+            };
+            return ChessStatus::Ok;
+        },
+        _ => return ChessStatus::NullPointer
+    }
+}
+
+/// Write the squares the piece on `square` can move to into `buffer`.
+///
+/// `count` is always set to the number of moves. If it exceeds `capacity`
+/// nothing is written and CHESS_STATUS_BUFFER_TOO_SMALL is returned, so
+/// the call can be repeated with a larger buffer. A piece never has more
+/// moves than the board has squares, at most 100 in Grand chess.
+///
+/// # Safety
+/// `game` must be a live handle, `square` a NUL terminated string, `count`
+/// a valid pointer and `buffer` must hold `capacity` squares.
+#[no_mangle]
+pub unsafe extern "C" fn chess_legal_moves(game: *const Game, square: *const c_char, buffer: *mut ChessSquare, capacity: usize, count: *mut usize) -> ChessStatus {
+    let game: &Game = match game.as_ref() {
+        Some(game) => game,
+        None => return ChessStatus::NullPointer
+    };
+    if count.is_null() || (buffer.is_null() && capacity > 0) {
+        return ChessStatus::NullPointer;
+    }
+    let square: &str = match read_str(square) {
+        Ok(square) => square,
+        Err(status) => return status
+    };
+
+    if Position::parse(square).is_none() {
+        return ChessStatus::InvalidPosition;
+    }
+    let moves = match game.get_possible_moves(square.to_string()) {
+        Some(moves) => moves,
+        None => return ChessStatus::NoPiece
+    };
+
+    return write_squares(&moves, buffer, capacity, count);
+}
+
+/// Put the duck on `square` after a move in Duck chess. The opponent moves next.
+///
+/// # Safety
+/// `game` must be a live handle and `square` a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn chess_place_duck(game: *mut Game, square: *const c_char) -> ChessStatus {
+    let game: &mut Game = match game.as_mut() {
+        Some(game) => game,
+        None => return ChessStatus::NullPointer
+    };
+    let square: &str = match read_str(square) {
+        Ok(square) => square,
+        Err(status) => return status
+    };
+
+    match game.try_place_duck(square) {
+        Ok(_) => return ChessStatus::Ok,
+        Err(error) => return error.into()
+    }
+}
+
+/// Write the squares the duck can be put on into `buffer`, none unless the
+/// game state is CHESS_GAME_STATE_DUCK_PLACEMENT. `count` and the status
+/// work as in `chess_legal_moves`.
+///
+/// # Safety
+/// `game` must be a live handle, `count` a valid pointer and `buffer` must
+/// hold `capacity` squares.
+#[no_mangle]
+pub unsafe extern "C" fn chess_duck_squares(game: *const Game, buffer: *mut ChessSquare, capacity: usize, count: *mut usize) -> ChessStatus {
+    let game: &Game = match game.as_ref() {
+        Some(game) => game,
+        None => return ChessStatus::NullPointer
+    };
+    if count.is_null() || (buffer.is_null() && capacity > 0) {
+        return ChessStatus::NullPointer;
+    }
+
+    return write_squares(&game.get_duck_squares(), buffer, capacity, count);
+}
+
+/// Store the number of bytes the FEN of the position takes in `out`, with
+/// the terminating NUL. Pockets, check counters and wide boards make it
+/// longer than on a standard board.
+///
+/// # Safety
+/// `game` must be a live handle and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn chess_fen_length(game: *const Game, out: *mut usize) -> ChessStatus {
+    match (game.as_ref(), out.is_null()) {
+        (Some(game), false) => {
+            *out = game.to_fen().len() + 1;
+            return ChessStatus::Ok;
+        },
+        _ => return ChessStatus::NullPointer
+    }
+}
+
+/// Write the position in FEN as a NUL terminated string into `buffer`. If it
+/// does not fit, nothing is written and CHESS_STATUS_BUFFER_TOO_SMALL is
+/// returned, `chess_fen_length` tells how large the buffer has to be.
+///
+/// # Safety
+/// `game` must be a live handle and `buffer` must hold `capacity` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chess_to_fen(game: *const Game, buffer: *mut c_char, capacity: usize) -> ChessStatus {
+    let game: &Game = match game.as_ref() {
+        Some(game) => game,
+        None => return ChessStatus::NullPointer
+    };
+    if buffer.is_null() {
+        return ChessStatus::NullPointer;
+    }
+
+    // Leave room for the terminating NUL
+    let fen: String = game.to_fen();
+    if fen.len() + 1 > capacity {
+        return ChessStatus::BufferTooSmall;
+    }
+
+    ptr::copy_nonoverlapping(fen.as_ptr() as *const c_char, buffer, fen.len());
+    *buffer.add(fen.len()) = 0;
+    return ChessStatus::Ok;
+}
+
+// Copy squares into a caller's buffer, if they fit
+unsafe fn write_squares(squares: &[Position], buffer: *mut ChessSquare, capacity: usize, count: *mut usize) -> ChessStatus {
+    *count = squares.len();
+    if squares.len() > capacity {
+        return ChessStatus::BufferTooSmall;
+    }
+
+    for (index, position) in squares.iter().enumerate() {
+        *buffer.add(index) = ChessSquare { column: position.column, row: position.row };
+    }
+    return ChessStatus::Ok;
+}
+
+// Borrow a C string as UTF-8
+unsafe fn read_str<'a>(string: *const c_char) -> Result<&'a str, ChessStatus> {
+    if string.is_null() {
+        return Err(ChessStatus::NullPointer);
+    }
+
+    match CStr::from_ptr(string).to_str() {
+        Ok(string) => return Ok(string),
+        Err(_) => return Err(ChessStatus::InvalidString)
+    }
+}
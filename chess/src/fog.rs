@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{Color, Dimensions, Game, Position, Role};
+
+/* Fog of war.
+ *
+ * A player only sees the squares their own pieces stand on or could move
+ * to. The game itself keeps full information and referees as usual, so
+ * capturing the king still ends it. Hand each player `view_for` their own
+ * color instead of the whole board.
+ */
+
+/// What one player can see of the board.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialBoard {
+    color: Color,
+    dimensions: Dimensions,
+
+    // Visible squares and what stands on them, squares in the fog are left out
+    squares: HashMap<Position, Option<(Color, Role)>>
+}
+
+impl Game {
+
+    /// Get the part of the board the given color can see.
+    pub fn view_for(&self, _color: Color) -> PartialBoard {
+        let mut squares: HashMap<Position, Option<(Color, Role)>> = HashMap::new();
+
+        for piece in self.board.values().filter(|piece| piece.color == _color) {
+            squares.insert(piece.position.clone(), Some((piece.color.clone(), piece.role.clone())));
+
+            for target in self.piece_moves(piece) {
+                let seen: Option<(Color, Role)> = self.get_piece_at(&target);
+                squares.insert(target, seen);
+            }
+        }
+
+        return PartialBoard { color: _color, dimensions: self.get_dimensions(), squares };
+    }
+}
+
+impl PartialBoard {
+
+    /// Get the color this view belongs to.
+    pub fn get_color(&self) -> Color {
+        return self.color.clone();
+    }
+
+    /// Whether a square can be seen.
+    pub fn is_visible(&self, _position: &Position) -> bool {
+        return self.squares.contains_key(_position);
+    }
+
+    /// Get the color and role of a piece on a visible square.
+    pub fn get_piece_at(&self, _position: &Position) -> Option<(Color, Role)> {
+        match self.squares.get(_position) {
+            Some(piece) => return piece.clone(),
+            None => return None
+        }
+    }
+
+    /// Get every visible square, ordered by square.
+    pub fn visible_squares(&self) -> Vec<Position> {
+        let mut squares: Vec<Position> = self.squares.keys().cloned().collect();
+        squares.sort();
+        return squares;
+    }
+}
+
+/// Output example, white pieces are upper case and the fog is "?":
+/// ? ? ? ? ? ? ? ?
+/// ? ? ? ? ? ? ? ?
+/// ? ? ? ? ? ? ? ?
+/// ? ? ? ? ? ? ? ?
+/// * * * * * * * *
+/// * * * * * * * *
+/// P P P P P P P P
+/// R N B Q K B N R
+impl fmt::Display for PartialBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut output: String = String::new();
+
+        for row in (1..=self.dimensions.rows).rev() {
+            let mut squares: Vec<String> = Vec::new();
+
+            for column in 1..=self.dimensions.columns {
+                let square: char = match self.squares.get(&Position { row, column }) {
+                    Some(Some((Color::White, role))) => role.to_char(),
+                    Some(Some((Color::Black, role))) => role.to_char().to_ascii_lowercase(),
+                    Some(None) => '*',
+                    None => '?'
+                };
+                squares.push(square.to_string());
+            }
+
+            output.push_str(&squares.join(" "));
+            output.push('\n');
+        }
+
+        write!(f, "{}", output)
+    }
+}
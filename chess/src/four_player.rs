@@ -0,0 +1,504 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{GameState, MoveError, Movement, Position, Role};
+
+/* Four-player chess on the 14x14 cross board.
+ *
+ * The board is 14x14 without the 3x3 corners. Red sits at the bottom,
+ * blue on the left, yellow at the top and green on the right, and they
+ * move in that order. Each player has the usual army on their edge of the
+ * board, the queen on the left of the king as seen by the player, and
+ * pawns move away from the own edge. There is no castling.
+ *
+ * - Free-for-all: a player whose king is captured is out and their pieces
+ *   leave the board. Once one player is left, the player with the most
+ *   points wins. Pawns promote to a queen on the 8th rank.
+ * - Teams: red and yellow play against blue and green, partners cannot
+ *   capture each other. The first captured king ends the game for both
+ *   teams. Pawns promote to a queen on the 11th rank.
+ *
+ * Captures give points: pawn 1, knight 3, bishop and rook 5, queen 9, a
+ * promoted queen 1 and the king 20.
+ */
+
+// Size of the board, the corners are cut off
+const SIZE: i8 = 14;
+const CORNER: i8 = 3;
+
+/// One of the four players.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Player {
+    Red,
+    Blue,
+    Yellow,
+    Green
+}
+
+/// How the four players play against each other.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FourPlayerMode {
+    FreeForAll,
+    Teams
+}
+
+impl Player {
+
+    /// Get the player who moves after this one.
+    pub fn next(&self) -> Player {
+        match self {
+            Player::Red => return Player::Blue,
+            Player::Blue => return Player::Yellow,
+            Player::Yellow => return Player::Green,
+            Player::Green => return Player::Red
+        }
+    }
+
+    /// Get the player sitting opposite, the partner in team games.
+    pub fn partner(&self) -> Player {
+        match self {
+            Player::Red => return Player::Yellow,
+            Player::Blue => return Player::Green,
+            Player::Yellow => return Player::Red,
+            Player::Green => return Player::Blue
+        }
+    }
+
+    // Direction pawns move into, as (column, row)
+    fn forward(&self) -> (i8, i8) {
+        match self {
+            Player::Red => return (0, 1),
+            Player::Blue => return (1, 0),
+            Player::Yellow => return (0, -1),
+            Player::Green => return (-1, 0)
+        }
+    }
+
+    // Ranks from the own edge of the board, 0 for the back rank
+    fn depth(&self, _position: &Position) -> i8 {
+        match self {
+            Player::Red => return _position.row - 1,
+            Player::Blue => return _position.column - 1,
+            Player::Yellow => return SIZE - _position.row,
+            Player::Green => return SIZE - _position.column
+        }
+    }
+
+    // Leftmost square of the back rank, as seen by the player
+    fn back_rank_start(&self) -> Position {
+        match self {
+            Player::Red => return Position { row: 1, column: 4 },
+            Player::Blue => return Position { row: 11, column: 1 },
+            Player::Yellow => return Position { row: 14, column: 11 },
+            Player::Green => return Position { row: 4, column: 14 }
+        }
+    }
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name: &str = match self {
+            Player::Red => "Red",
+            Player::Blue => "Blue",
+            Player::Yellow => "Yellow",
+            Player::Green => "Green"
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FourPlayerPiece {
+    player: Player,
+    role: Role,
+    promoted: bool
+}
+
+/// A game of four-player chess.
+pub struct FourPlayerGame {
+    state: GameState,
+    board: HashMap<Position, FourPlayerPiece>,
+    mode: FourPlayerMode,
+    active_player: Player,
+
+    // Players still on the board, and the points they scored
+    players: Vec<Player>,
+    points: HashMap<Player, u32>,
+
+    // Moves played so far, e.g. "Nj1-i3"
+    history: Vec<String>,
+    winners: Vec<Player>
+}
+
+impl FourPlayerGame {
+
+    /// Initialises a new cross board with the four armies.
+    pub fn new(mode: FourPlayerMode) -> FourPlayerGame {
+        let back_rank: [Role; 8] = [Role::Rook, Role::Knight, Role::Bishop, Role::Queen, Role::King, Role::Bishop, Role::Knight, Role::Rook];
+        let players: Vec<Player> = vec![Player::Red, Player::Blue, Player::Yellow, Player::Green];
+        let mut board: HashMap<Position, FourPlayerPiece> = HashMap::new();
+
+        for player in players.iter() {
+            let start: Position = player.back_rank_start();
+            let forward: (i8, i8) = player.forward();
+
+            // The back rank runs to the right of the player, forward turned clockwise
+            let right: (i8, i8) = (forward.1, -forward.0);
+
+            for (index, role) in back_rank.iter().enumerate() {
+                let column: i8 = start.column + right.0 * index as i8;
+                let row: i8 = start.row + right.1 * index as i8;
+
+                board.insert(Position { row, column }, FourPlayerPiece { player: *player, role: role.clone(), promoted: false });
+                board.insert(Position { row: row + forward.1, column: column + forward.0 }, FourPlayerPiece { player: *player, role: Role::Pawn, promoted: false });
+            }
+        }
+
+        return FourPlayerGame {
+            state: GameState::InProgress,
+            board,
+            mode,
+            active_player: Player::Red,
+            points: players.iter().map(|player| (*player, 0)).collect(),
+            players,
+            history: Vec::new(),
+            winners: Vec::new()
+        };
+    }
+
+    // Game with only the given pieces on the board
+    #[cfg(test)]
+    pub(crate) fn with_pieces(mode: FourPlayerMode, active_player: Player, pieces: &[(&str, Player, Role)]) -> FourPlayerGame {
+        let mut game: FourPlayerGame = FourPlayerGame::new(mode);
+        game.board = pieces.iter()
+            .map(|(square, player, role)| (parse_square(square).unwrap(), FourPlayerPiece { player: *player, role: role.clone(), promoted: false }))
+            .collect();
+        game.active_player = active_player;
+        return game;
+    }
+
+    /// Same as `Game::try_make_move`, for the player whose turn it is.
+    pub fn try_make_move(&mut self, _from: &str, _to: &str) -> Result<GameState, MoveError> {
+        if self.state == GameState::GameOver {
+            return Err(MoveError::GameOver);
+        }
+
+        let from_pos: Position = parse_square(_from)?;
+        let to_pos: Position = parse_square(_to)?;
+
+        let piece: &FourPlayerPiece = match self.board.get(&from_pos) {
+            Some(piece) => piece,
+            None => return Err(MoveError::NoPiece)
+        };
+        if piece.player != self.active_player {
+            return Err(MoveError::NotYourTurn);
+        }
+        if !self.piece_moves(&from_pos).contains(&to_pos) {
+            return Err(MoveError::IllegalMove);
+        }
+
+        let mut piece: FourPlayerPiece = self.board.remove(&from_pos).unwrap();
+        let captured: Option<FourPlayerPiece> = self.board.remove(&to_pos);
+
+        // Write down the move, pawns without a letter
+        let mut notation: String = String::new();
+        if piece.role != Role::Pawn {
+            notation.push(piece.role.to_char());
+        }
+        let separator: char = if captured.is_some() { 'x' } else { '-' };
+        notation.push_str(&format!("{}{}{}", from_pos, separator, to_pos));
+
+        // Pawns turn into a queen on their promotion rank
+        if piece.role == Role::Pawn && piece.player.depth(&to_pos) == self.promotion_depth() {
+            piece.role = Role::Queen;
+            piece.promoted = true;
+            notation.push_str("=Q");
+        }
+        self.history.push(notation);
+        self.board.insert(to_pos, piece);
+
+        if let Some(captured) = captured {
+            *self.points.get_mut(&self.active_player).unwrap() += capture_points(&captured);
+
+            if captured.role == Role::King {
+                self.king_captured(captured.player);
+            }
+        }
+
+        self.pass_turn();
+        return Ok(self.state);
+    }
+
+    /// Give up the game for a player. In free-for-all the others play on.
+    pub fn resign(&mut self, _player: Player) -> GameState {
+        if self.state == GameState::GameOver || !self.players.contains(&_player) {
+            return self.state;
+        }
+
+        match self.mode {
+            FourPlayerMode::Teams => {
+                self.winners = vec![_player.next(), _player.next().partner()];
+                self.winners.sort();
+                self.state = GameState::GameOver;
+            },
+            FourPlayerMode::FreeForAll => {
+                self.eliminate(_player);
+                if self.active_player == _player {
+                    self.pass_turn();
+                }
+            }
+        }
+
+        return self.state;
+    }
+
+    /// If a piece of any player is standing on the given square, return
+    /// all squares it can move to.
+    pub fn get_possible_moves(&self, _position: &str) -> Option<Vec<Position>> {
+        let position: Position = parse_square(_position).ok()?;
+        if !self.board.contains_key(&position) {
+            return None;
+        }
+        return Some(self.piece_moves(&position));
+    }
+
+    /// Get every move the active player can make as (from, to) pairs, ordered by square.
+    pub fn get_legal_moves(&self) -> Vec<(Position, Position)> {
+        let mut moves: Vec<(Position, Position)> = Vec::new();
+
+        if self.state == GameState::GameOver {
+            return moves;
+        }
+
+        for (position, piece) in self.board.iter() {
+            if piece.player != self.active_player {
+                continue;
+            }
+
+            for target in self.piece_moves(position) {
+                moves.push((position.clone(), target));
+            }
+        }
+
+        moves.sort();
+        return moves;
+    }
+
+    /// Get the player and role of the piece standing on a square.
+    pub fn get_piece_at(&self, _position: &Position) -> Option<(Player, Role)> {
+        match self.board.get(_position) {
+            Some(piece) => return Some((piece.player, piece.role.clone())),
+            None => return None
+        }
+    }
+
+    /// Get the player whose turn it is.
+    pub fn get_active_player(&self) -> Player {
+        return self.active_player;
+    }
+
+    /// Get the current game state.
+    pub fn get_game_state(&self) -> GameState {
+        return self.state;
+    }
+
+    /// Get how the players play against each other.
+    pub fn get_mode(&self) -> FourPlayerMode {
+        return self.mode;
+    }
+
+    /// Get the points a player scored by capturing.
+    pub fn get_points(&self, _player: Player) -> u32 {
+        return self.points[&_player];
+    }
+
+    /// Whether a player is out of the game.
+    pub fn is_eliminated(&self, _player: Player) -> bool {
+        return !self.players.contains(&_player);
+    }
+
+    /// Get the winners once the game is over, both partners in team games
+    /// and everyone sharing the most points in free-for-all.
+    pub fn get_winners(&self) -> Vec<Player> {
+        return self.winners.clone();
+    }
+
+    /// Get the moves played so far.
+    pub fn get_history(&self) -> Vec<String> {
+        return self.history.clone();
+    }
+
+    // Every square the piece on a square can move to
+    fn piece_moves(&self, _position: &Position) -> Vec<Position> {
+        let piece: &FourPlayerPiece = match self.board.get(_position) {
+            Some(piece) => piece,
+            None => return Vec::new()
+        };
+        let movement: &Movement = piece.role.movement();
+
+        // Partners' pieces block like own pieces in team games
+        let occupant = |square: &Position| -> Option<bool> {
+            match self.board.get(square) {
+                Some(other) => return Some(!self.friendly(piece.player, other.player)),
+                None => return None
+            }
+        };
+        let forward: (i8, i8) = piece.player.forward();
+        let mut moves: Vec<Position> = movement.targets(_position, forward, &occupant, &on_board);
+
+        // Pawns on their start rank may step twice
+        if piece.role == Role::Pawn && piece.player.depth(_position) == 1 {
+            let step: Position = Position { row: _position.row + forward.1, column: _position.column + forward.0 };
+            let double_step: Position = Position { row: step.row + forward.1, column: step.column + forward.0 };
+            if !self.board.contains_key(&step) && !self.board.contains_key(&double_step) {
+                moves.push(double_step);
+            }
+        }
+
+        return moves;
+    }
+
+    // Whether two players are on the same side
+    fn friendly(&self, _player: Player, _other: Player) -> bool {
+        return _player == _other || (self.mode == FourPlayerMode::Teams && _player.partner() == _other);
+    }
+
+    // Ranks from the own edge where pawns promote
+    fn promotion_depth(&self) -> i8 {
+        match self.mode {
+            FourPlayerMode::FreeForAll => return 7,
+            FourPlayerMode::Teams => return 10
+        }
+    }
+
+    // Hand the turn to the next player who can move. In free-for-all a player
+    // without a move is out, in team games the turn goes past them.
+    fn pass_turn(&mut self) {
+        let mut skipped: usize = 0;
+
+        while self.state != GameState::GameOver {
+            self.active_player = self.next_player(self.active_player);
+            if !self.get_legal_moves().is_empty() {
+                return;
+            }
+
+            match self.mode {
+                FourPlayerMode::FreeForAll => self.eliminate(self.active_player),
+                FourPlayerMode::Teams => {
+
+                    // Nobody can move, no side wins
+                    skipped += 1;
+                    if skipped == self.players.len() {
+                        self.state = GameState::GameOver;
+                    }
+                }
+            }
+        }
+    }
+
+    // Next player still on the board
+    fn next_player(&self, _player: Player) -> Player {
+        let mut player: Player = _player.next();
+        while !self.players.contains(&player) && player != _player {
+            player = player.next();
+        }
+        return player;
+    }
+
+    // The king of a player fell to the active player
+    fn king_captured(&mut self, _player: Player) {
+        match self.mode {
+            FourPlayerMode::Teams => {
+                self.winners = vec![self.active_player, self.active_player.partner()];
+                self.winners.sort();
+                self.state = GameState::GameOver;
+            },
+            FourPlayerMode::FreeForAll => self.eliminate(_player)
+        }
+    }
+
+    // Take a player and their pieces off the board, the game ends with one player left
+    fn eliminate(&mut self, _player: Player) {
+        self.players.retain(|player| *player != _player);
+        self.board.retain(|_, piece| piece.player != _player);
+
+        if self.players.len() > 1 {
+            return;
+        }
+
+        let best: u32 = self.points.values().cloned().max().unwrap_or(0);
+        self.winners = self.points.iter().filter(|(_, points)| **points == best).map(|(player, _)| *player).collect();
+        self.winners.sort();
+        self.state = GameState::GameOver;
+    }
+}
+
+/// Output example, red pieces at the bottom:
+///          yR yN yB yK yQ yB yN yR
+///          yP yP yP yP yP yP yP yP
+///          .  .  .  .  .  .  .  .
+/// bR bP .  .  .  .  .  .  .  .  .  .  gP gR
+/// ...
+impl fmt::Display for FourPlayerGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut output: String = String::new();
+
+        for row in (1..=SIZE).rev() {
+            let mut squares: Vec<String> = Vec::new();
+
+            for column in 1..=SIZE {
+                let position: Position = Position { row, column };
+                let square: String = match self.board.get(&position) {
+                    Some(piece) => {
+                        let player: char = match piece.player {
+                            Player::Red => 'r',
+                            Player::Blue => 'b',
+                            Player::Yellow => 'y',
+                            Player::Green => 'g'
+                        };
+                        format!("{}{}", player, piece.role.to_char())
+                    },
+                    None if on_board(&position) => String::from(". "),
+                    None => String::from("  ")
+                };
+                squares.push(square);
+            }
+
+            output.push_str(squares.join(" ").trim_end());
+            output.push('\n');
+        }
+
+        write!(f, "{}", output)
+    }
+}
+
+// Whether a square is part of the cross board
+fn on_board(_position: &Position) -> bool {
+    let inside = |value: i8| (1..=SIZE).contains(&value);
+    let corner = |value: i8| value <= CORNER || value > SIZE - CORNER;
+    return inside(_position.row) && inside(_position.column) && !(corner(_position.row) && corner(_position.column));
+}
+
+// Read the name of a square of the cross board, e.g. "h2" or "n11"
+fn parse_square(_name: &str) -> Result<Position, MoveError> {
+    match Position::parse(_name) {
+        Some(position) if on_board(&position) => return Ok(position),
+        _ => return Err(MoveError::InvalidPosition)
+    }
+}
+
+// Points for capturing a piece
+fn capture_points(_piece: &FourPlayerPiece) -> u32 {
+    if _piece.promoted {
+        return 1;
+    }
+
+    match _piece.role {
+        Role::Pawn => return 1,
+        Role::Knight => return 3,
+        Role::Bishop | Role::Rook => return 5,
+        Role::Queen => return 9,
+        Role::King => return 20,
+        _ => return 0
+    }
+}
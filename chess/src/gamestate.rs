@@ -3,5 +3,8 @@ pub enum GameState {
     InProgress,
     Promotion,
     Check,
-    GameOver
+    GameOver,
+
+    // The duck has to be placed before the turn passes, in Duck chess
+    DuckPlacement
 }
\ No newline at end of file
@@ -1,33 +1,117 @@
+// The code base prefers explicit returns and matches over the terser forms clippy suggests
+#![allow(
+    clippy::needless_return,
+    clippy::single_match,
+    clippy::needless_late_init,
+    clippy::manual_unwrap_or_default,
+    clippy::collapsible_match,
+    clippy::needless_bool,
+    clippy::needless_bool_assign,
+    clippy::bool_comparison,
+    clippy::unused_unit
+)]
+
 use std::fmt;
 use std::collections::HashMap;
 
 mod piece;
 mod gamestate;
+mod moves;
+mod error;
+mod fen;
+mod pgn;
+mod castling;
+mod chess960;
+mod setup;
+mod variant;
+mod fog;
+mod bughouse;
+mod four_player;
+mod attacks;
+mod assist;
+mod tactics;
+mod see;
+pub mod engine;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
 
-use piece::position::Position;
-use piece::color::Color;
-use piece::role::Role;
+pub use piece::position::Position;
+pub use piece::color::Color;
+pub use piece::role::{Role, RoleInfo, ROLES};
+pub use piece::dimensions::Dimensions;
+pub use piece::betza::Movement;
 use piece::Piece;
-use gamestate::GameState;
+pub use gamestate::GameState;
+pub use moves::Move;
+pub use error::MoveError;
+pub use fen::FenError;
+pub use chess960::{chess960_back_rank, CHESS960_POSITIONS};
+pub use setup::{SetupBuilder, SetupError};
+pub use variant::Variant;
+pub use fog::PartialBoard;
+pub use bughouse::{Bughouse, BughouseBoard, BughouseTeam};
+pub use four_player::{FourPlayerGame, FourPlayerMode, Player};
+pub use attacks::AttackMap;
+pub use tactics::Tactic;
 
 /* IMPORTANT:
  * - Document well!
  * - Write well structured and clean code!
  */
 
+#[derive(Clone)]
 pub struct Game {
     state: GameState,
     board: HashMap<Position, Piece>,
     active_color: Color,
 
-    white_king: &Piece,
-    black_King: &Piece
+    // Moves since the last capture or pawn move, and the current move number
+    halfmove_clock: u32,
+    fullmove_number: u32,
+
+    // Moves played so far in algebraic notation, written down unless the engine
+    // plays them on a copy of the position while searching
+    history: Vec<String>,
+    record: bool,
+
+    // Rules the game is played by, castling follows the Chess960 rules and notation
+    variant: Variant,
+    chess960: bool,
+
+    // Checks each color has given, for Three-check
+    white_checks: u32,
+    black_checks: u32,
+
+    // Captured pieces each color may drop, for Crazyhouse
+    white_pocket: Vec<Role>,
+    black_pocket: Vec<Role>,
+
+    // Square of the duck, for Duck chess
+    duck: Option<Position>,
+
+    // Zobrist keys of the pieces on the board, see `zobrist_hash`
+    board_hash: u64,
+
+    // Whether moves leaving the own king capturable need to be confirmed
+    king_danger_assist: bool,
+
+    // Position the game started from, for the PGN header
+    start_fen: String
 }
 
 impl Game {
 
     // Initialises a new board with pieces.
     pub fn new() -> Game {
+        return Self::from_back_rank(&[Role::Rook, Role::Knight, Role::Bishop, Role::Queen, Role::King, Role::Bishop, Role::Knight, Role::Rook], false);
+    }
+
+    // Sets up pawns and the given back rank, mirrored for black
+    fn from_back_rank(back_rank: &[Role; 8], chess960: bool) -> Game {
 
         //Board
         let mut _board: HashMap<Position, Piece> = HashMap::new();
@@ -38,42 +122,39 @@ impl Game {
             Self::insert_piece(&mut _board, Color::Black, Role::Pawn, Position {row: 7, column: x});
         }
 
-        // Insert rooks
-        Self::insert_piece(&mut _board, Color::Black, Role::Rook, Position {row: 8, column: 1});
-        Self::insert_piece(&mut _board, Color::Black, Role::Rook, Position {row: 8, column: 8});
-        Self::insert_piece(&mut _board, Color::White, Role::Rook, Position {row: 1, column: 1});
-        Self::insert_piece(&mut _board, Color::White, Role::Rook, Position {row: 1, column: 8});
-
-        // Insert knights
-        Self::insert_piece(&mut _board, Color::Black, Role::Knight, Position {row: 8, column: 2});
-        Self::insert_piece(&mut _board, Color::Black, Role::Knight, Position {row: 8, column: 7});
-        Self::insert_piece(&mut _board, Color::White, Role::Knight, Position {row: 1, column: 2});
-        Self::insert_piece(&mut _board, Color::White, Role::Knight, Position {row: 1, column: 7});
-
-        // Insert bishops
-        Self::insert_piece(&mut _board, Color::Black, Role::Bishop, Position {row: 8, column: 3});
-        Self::insert_piece(&mut _board, Color::Black, Role::Bishop, Position {row: 8, column: 6});
-        Self::insert_piece(&mut _board, Color::White, Role::Bishop, Position {row: 1, column: 3});
-        Self::insert_piece(&mut _board, Color::White, Role::Bishop, Position {row: 1, column: 6});
-
-        // Insert kings
-        Self::insert_piece(&mut _board, Color::Black, Role::King, Position {row: 8, column: 4});
-        Self::insert_piece(&mut _board, Color::White, Role::King, Position {row: 1, column: 5});
-
-        // Set references
-        white_king = _board.get(Position {row: 8, column: 4});
-        black_king = _board.get(Position {row: 1, column: 5});
-
-        // Insert queens
-        Self::insert_piece(&mut _board, Color::Black, Role::Queen, Position {row: 8, column: 5});
-        Self::insert_piece(&mut _board, Color::White, Role::Queen, Position {row: 1, column: 4});
+        // Insert the other pieces, black on the same files as white
+        for (x, role) in (1..9).zip(back_rank.iter()) {
+            Self::insert_piece(&mut _board, Color::White, role.clone(), Position {row: 1, column: x});
+            Self::insert_piece(&mut _board, Color::Black, role.clone(), Position {row: 8, column: x});
+        }
 
         // Initialize Game
-        return Game {
+        return Self::with_board(_board, Color::White, 0, 1, Variant::Standard, chess960);
+    }
+
+    // Starts a game from a set up board, remembering the start position
+    fn with_board(board: HashMap<Position, Piece>, active_color: Color, halfmove_clock: u32, fullmove_number: u32, variant: Variant, chess960: bool) -> Game {
+        let mut game: Game = Game {
             state: GameState::InProgress,
-            board: _board,
-            active_color: Color::White
-        }
+            board_hash: engine::board_hash(&board),
+            board,
+            active_color,
+            halfmove_clock,
+            fullmove_number,
+            history: Vec::new(),
+            record: true,
+            variant,
+            chess960,
+            white_checks: 0,
+            black_checks: 0,
+            white_pocket: Vec::new(),
+            black_pocket: Vec::new(),
+            duck: None,
+            king_danger_assist: false,
+            start_fen: String::new()
+        };
+        game.start_fen = game.to_fen();
+        return game;
     }
 
     // Function to insert pieces
@@ -84,7 +165,8 @@ impl Game {
                 color: _color,
                 role: _role,
                 position: _position,
-                has_moved: false
+                has_moved: false,
+                promoted: false
             }
         );
     }
@@ -92,151 +174,299 @@ impl Game {
     /// If the current game state is InProgress and the move is legal, 
     /// move a piece and return the resulting state of the game.
     pub fn make_move(&mut self, _from: String, _to: String) -> Option<GameState> {
+        match self.try_make_move(&_from, &_to) {
+            Ok(state) => return Some(state),
+            Err(error) => {
+                println!("{}", error);
+                return Some(self.state);
+            }
+        }
+    }
 
-        // Game is either over or waiting for promotion
-        if self.state == GameState::GameOver || self.state == GameState::Promotion {
-            return Some(self.state);
+    /// Same as `make_move`, but tells why a move was rejected instead of printing it.
+    pub fn try_make_move(&mut self, _from: &str, _to: &str) -> Result<GameState, MoveError> {
+        return self.try_make_move_confirmed(_from, _to, false);
+    }
+
+    /// Same as `try_make_move`, with the king-danger assist on a move leaving the own
+    /// king capturable is only made when `confirm` is set.
+    pub fn try_make_move_confirmed(&mut self, _from: &str, _to: &str, confirm: bool) -> Result<GameState, MoveError> {
+
+        // Game is either over or waiting for promotion or the duck
+        match self.state {
+            GameState::GameOver => return Err(MoveError::GameOver),
+            GameState::Promotion => return Err(MoveError::PromotionPending),
+            GameState::DuckPlacement => return Err(MoveError::DuckPending),
+            _ => ()
         }
 
-        // Convert String _from to Position
-        let from_pos: Position; 
-        match Position::new(_from) {
-            Some(position) => from_pos = position,
-            None => return Some(self.state)
-        };
+        // Convert _from and _to to Positions
+        let from_pos: Position = self.parse_square(_from)?;
+        let to_pos: Position = self.parse_square(_to)?;
+        let to_pos: Position = self.castling_target(&from_pos, &to_pos);
 
-        // Convert String _to to Position
-        let to_pos: Position; 
-        match Position::new(_to) {
-            Some(position) => to_pos = position,
-            None => return Some(self.state)
+        // Get piece at position
+        let piece: &Piece = match self.board.get(&from_pos) {
+            Some(piece) => piece,
+            None => return Err(MoveError::NoPiece)
         };
 
-        // Get piece at position
-        match self.board.get(&from_pos) {
-            Some(piece) => {
+        // Check if piece is of correct color
+        if piece.color != self.active_color {
+            return Err(MoveError::NotYourTurn);
+        }
 
-                // Check if piece is of correct color
-                if piece.color != self.active_color { 
-                    println!("Not your turn");
-                    return Some(self.state);
-                }
+        // Check if desired move is possible
+        if !self.piece_moves(piece).contains(&to_pos) {
+            return Err(MoveError::IllegalMove);
+        }
 
-                // Get possible moves
-                match piece.get_possible_moves(&self.board) {
-                    Some(moves) => {
-
-                        // Check if desired move is possible
-                        if moves.contains(&to_pos) {
-                            
-                            // Moves piece and possibly removes another piece
-                            self.board.remove(&to_pos);
-                            let mut _piece: piece::Piece = self.board.remove(&from_pos).unwrap();
-
-                            // Modifies piece
-                            if _piece.has_moved != true {
-                                _piece.has_moved = true;
-                            };
-
-                            // Sets new place (inside piece)
-                            _piece.position = to_pos.clone();
-
-                            // Check if piece is pawn ready to be promoted
-                            if _piece.role == Role::Pawn && (_piece.position.row == 1 || _piece.position.row == 8) {
-                                self.state = GameState::Promotion;
-                            }
-
-                            // Inserts piece in board
-                            self.board.insert(to_pos, _piece);
-
-                            // Switches active color
-                            self.active_color = match self.active_color {
-                                Color::White => Color::Black,
-                                Color::Black => Color::White
-                            };
-
-                        } else {
-                            println!("Illegal move");
-                            return Some(self.state);
-                        };
-                    },
-                    None => {
-                        println!("No possible moves");
-                        return Some(self.state);
-                    }
-                }
-            },
-            None => {
-                println!("No piece at speficied position");
-                return Some(self.state);
-            }
+        // The move is legal, but the assist wants to hear it is meant
+        if self.king_danger_assist && !confirm && self.endangers_king(&from_pos, &to_pos) {
+            return Err(MoveError::SuicidalMove);
         }
 
-        // Check if state should be changed
-        match active_color {
-            Color::Black => (
-                if !board.contains(black_king) {
-                    self.state = GameState::GameOver;
-                }
-            ),
-            Color::White => (
-                if !board.contains(white_king) {
-                    self.state = GameState::GameOver;
+        if self.is_castling(&from_pos, &to_pos) {
+
+            // Moves king and rook at once
+            self.castle(&from_pos, &to_pos);
+        } else {
+
+            // Write down the move before the board changes
+            let notation: Option<String> = if self.record { Some(self.move_notation(&from_pos, &to_pos)) } else { None };
+
+            // Moves piece and possibly removes another piece
+            let captured: Option<Piece> = self.take_piece(&to_pos);
+            let mut _piece: piece::Piece = self.take_piece(&from_pos).unwrap();
+
+            // Update move counter
+            if _piece.role == Role::Pawn || captured.is_some() {
+                self.halfmove_clock = 0;
+            } else {
+                self.halfmove_clock += 1;
+            }
+            if let Some(notation) = notation {
+                self.history.push(notation);
+            }
+
+            // Modifies piece
+            _piece.has_moved = true;
+
+            // Sets new place (inside piece)
+            _piece.position = to_pos.clone();
+
+            // Inserts piece in board
+            self.put_piece(_piece);
+
+            // Some variants do more than take the captured piece away
+            if let Some(captured) = captured {
+                self.after_capture(&to_pos, captured);
+            }
+
+            // Check if piece is pawn ready to be promoted
+            if let Some(_piece) = self.board.get(&to_pos) {
+                if _piece.role == Role::Pawn && (_piece.position.row == 1 || _piece.position.row == self.get_dimensions().rows) {
+                    self.state = GameState::Promotion;
                 }
-            )
+            }
         }
 
         // Check game state
-        return Some(self.state);
+        return Ok(self.finish_move());
+    }
+
+    // Hand the turn to the other color once a piece has moved or dropped,
+    // in Duck chess the duck is placed first unless the move won the game
+    fn finish_move(&mut self) -> GameState {
+        if self.variant == Variant::Duck && !self.has_lost(&self.active_color.opponent()) {
+            if self.state != GameState::Promotion {
+                self.state = GameState::DuckPlacement;
+            }
+            return self.state;
+        }
+        return self.pass_turn();
+    }
+
+    // Switch the active color and look at the new position
+    pub(crate) fn pass_turn(&mut self) -> GameState {
+        if self.active_color == Color::Black {
+            self.fullmove_number += 1;
+        }
+
+        // Switches active color
+        self.active_color = self.active_color.opponent();
+
+        // Check if state should be changed
+        self.update_state();
+        return self.state;
     }
 
     /// Set the piece type that a peasant becames following a promotion.
     pub fn set_promotion(&mut self, _pos: String, _role: Role) -> () {
-        if self.state != GameState::Promotion || _role == Role::Pawn || _role == Role::King {
-            println!("Promotion not allowed");
-            return;
+        if let Err(error) = self.try_set_promotion(&_pos, _role) {
+            println!("{}", error);
         }
+    }
 
-        // Convert String _to to Position
-        let pos: Position; 
-        match Position::new(_pos) {
-            Some(position) => pos = position,
-            None => return 
-        };
+    /// Same as `set_promotion`, but tells why the promotion was rejected instead of printing it.
+    pub fn try_set_promotion(&mut self, _pos: &str, _role: Role) -> Result<GameState, MoveError> {
+        if self.state != GameState::Promotion || !self.allows_promotion(&_role) {
+            return Err(MoveError::PromotionNotAllowed);
+        }
+
+        // Convert _pos to Position
+        let pos: Position = self.parse_square(_pos)?;
 
         // Check if piece exists at position, If, remove it to replace it
-        match self.board.remove(&pos) {
+        match self.take_piece(&pos) {
             Some(piece) => {
-                if piece.color == self.active_color && piece.role == Role::Pawn && (piece.position.row == 1 || piece.position.row == 8) {
+                // The active color has already been switched by the promoting move,
+                // in Duck chess it only switches once the duck is placed
+                let mover: Color = if self.variant == Variant::Duck { self.active_color.clone() } else { self.active_color.opponent() };
+                if piece.color == mover && piece.role == Role::Pawn && (piece.position.row == 1 || piece.position.row == self.get_dimensions().rows) {
+
+                    // Add the new piece to the notation of the promoting move
+                    if let Some(notation) = self.history.last_mut() {
+                        notation.push('=');
+                        notation.push(_role.to_char());
+                    }
 
                     // Insert new piece
-                    self.board.insert(piece.position.clone(), Piece {
+                    self.put_piece(Piece {
                         color: piece.color,
                         role: _role,
                         position: piece.position,
-                        has_moved: true
+                        has_moved: true,
+                        promoted: true
                     });
 
                 } else {
-                    println!("Not a valid piece")
+
+                    // Put the piece back and keep waiting for a valid promotion
+                    self.put_piece(piece);
+                    return Err(MoveError::PromotionNotAllowed);
                 };
             },
-            None => println!("Not a valid position")
+            None => return Err(MoveError::NoPiece)
+        }
+
+        // The duck is still to be placed
+        if self.variant == Variant::Duck {
+            self.state = GameState::DuckPlacement;
+            return Ok(self.state);
         }
 
-        // Continue game
+        // Continue game, unless the new piece decides it
         self.state = GameState::InProgress;
+        self.update_state();
+        return Ok(self.state);
+    }
+
+    // Update the state after a move. The game ends once a side has lost,
+    // the color which is not active is the winner then.
+    fn update_state(&mut self) {
+        let waiting: Color = self.active_color.opponent();
+
+        // A check lasts one move
+        if self.state == GameState::Check {
+            self.state = GameState::InProgress;
+        }
+
+        // Checks are counted once a promotion is done
+        let check: bool = self.state != GameState::Promotion && self.reports_check() && self.is_check();
+        if check {
+            match waiting {
+                Color::White => self.white_checks += 1,
+                Color::Black => self.black_checks += 1
+            }
+        }
+
+        if self.has_lost(&self.active_color) {
+            self.state = GameState::GameOver;
+        } else if self.has_lost(&waiting) {
+            self.active_color = waiting;
+            self.state = GameState::GameOver;
+        } else if check {
+            self.state = GameState::Check;
+        }
+    }
+
+    /// Whether the king of the active color could be captured right now.
+    pub fn is_check(&self) -> bool {
+        return king_attacked(&self.board, &self.get_dimensions(), &self.active_color);
+    }
+
+    /// Get how many times a color has given check.
+    pub fn get_checks(&self, _color: &Color) -> u32 {
+        match _color {
+            Color::White => return self.white_checks,
+            Color::Black => return self.black_checks
+        }
     }
 
     /// Get the current game state.
     pub fn get_game_state(&self) -> GameState {
         return self.state;
     }
+
+    /// Give up the game. The other color wins.
+    pub fn resign(&mut self, _color: Color) -> GameState {
+        if self.state == GameState::GameOver {
+            println!("Game is already over");
+            return self.state;
+        }
+
+        // The color which is not active is the winner when the game is over
+        self.active_color = _color;
+        self.state = GameState::GameOver;
+        return self.state;
+    }
+
+    /// Get the winning color once the game is over.
+    pub fn get_winner(&self) -> Option<Color> {
+        if self.state != GameState::GameOver {
+            return None;
+        }
+
+        match self.active_color {
+            Color::White => return Some(Color::Black),
+            Color::Black => return Some(Color::White)
+        }
+    }
+
+    /// Whether castling follows the Chess960 rules.
+    pub fn is_chess960(&self) -> bool {
+        return self.chess960;
+    }
+
+    // Every square a piece can move to under the rules of the variant, castling included
+    pub(crate) fn piece_moves(&self, piece: &Piece) -> Vec<Position> {
+        let mut moves: Vec<Position> = match piece.get_blocked_moves(&self.board, &self.get_dimensions(), &self.blocked_squares()) {
+            Some(moves) => moves,
+            None => Vec::new()
+        };
+        moves.append(&mut self.castling_moves(piece));
+        return self.filter_moves(piece, moves);
+    }
+
+    // Read the name of a square, squares off the board of the game are invalid
+    pub(crate) fn parse_square(&self, _name: &str) -> Result<Position, MoveError> {
+        match Position::parse(_name) {
+            Some(position) if self.get_dimensions().contains(&position) => return Ok(position),
+            _ => return Err(MoveError::InvalidPosition)
+        }
+    }
+
+    /// Get the color whose turn it is.
+    pub fn get_active_color(&self) -> Color {
+        return self.active_color.clone();
+    }
     
     /// If a piece is standing on the given tile, return all possible 
     /// new positions of that piece. Don't forget to the rules for check. 
     /// 
-    /// (optional) Don't forget to include en passent and castling.
+    /// Castling is listed as the king moving onto its own rook.
+    /// (optional) Don't forget to include en passent.
     pub fn get_possible_moves(&self, _position: String) -> Option<Vec<Position>> {
 
         // Convert String _to to Position
@@ -248,22 +478,70 @@ impl Game {
 
         // Check position
         match self.board.get(&_pos) {
-            Some(piece) => return piece.get_possible_moves(&self.board),
+            Some(piece) => return Some(self.piece_moves(piece)),
             None => return None
         };
     }
+
+    /// Get every move the active color can make as (from, to) pairs, ordered by square.
+    pub fn get_legal_moves(&self) -> Vec<(Position, Position)> {
+        let mut moves: Vec<(Position, Position)> = Vec::new();
+
+        // No moves while the game is over or waiting for promotion or the duck
+        if self.state == GameState::GameOver || self.state == GameState::Promotion || self.state == GameState::DuckPlacement {
+            return moves;
+        }
+
+        for piece in self.board.values() {
+            if piece.color != self.active_color {
+                continue;
+            }
+
+            for target in self.piece_moves(piece) {
+                moves.push((piece.position.clone(), target));
+            }
+        }
+
+        // The board is a HashMap, sort to get the same order every time
+        moves.sort();
+        return moves;
+    }
+
+    /// Get the color and role of the piece standing on a position.
+    pub fn get_piece_at(&self, _position: &Position) -> Option<(Color, Role)> {
+        match self.board.get(_position) {
+            Some(piece) => return Some((piece.color.clone(), piece.role.clone())),
+            None => return None
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        return Game::new();
+    }
+}
+
+// Check if a piece of the other color could capture the king of the given color
+pub(crate) fn king_attacked(board: &HashMap<Position, Piece>, dimensions: &Dimensions, _color: &Color) -> bool {
+    let king: &Piece = match board.values().find(|piece| piece.role == Role::King && piece.color == *_color) {
+        Some(king) => king,
+        None => return false
+    };
+
+    return !attacks::attackers(board, dimensions, &[], &king.position, &_color.opponent()).is_empty();
 }
 
 /// Output example:
 /// |:----------------------:|
-/// | R  Kn B  K  Q  B  Kn R |
+/// | R  Kn B  Q  K  B  Kn R |
 /// | P  P  P  P  P  P  P  P |
 /// | *  *  *  *  *  *  *  * |
 /// | *  *  *  *  *  *  *  * |
 /// | *  *  *  *  *  *  *  * |
 /// | *  *  *  *  *  *  *  * |
 /// | P  P  P  P  P  P  P  P |
-/// | R  Kn B  K  Q  B  Kn R |
+/// | R  Kn B  Q  K  B  Kn R |
 /// |:----------------------:|
 impl fmt::Debug for Game {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -271,28 +549,21 @@ impl fmt::Debug for Game {
         // Output string
         let mut output: String = Default::default();
 
-        for x in (1..9).rev() {
-            for y in 1..9 {
+        let dimensions: Dimensions = self.get_dimensions();
+        for x in (1..=dimensions.rows).rev() {
+            for y in 1..=dimensions.columns {
                 let piece: &str = match self.board.get( &Position { row: x, column: y} ) {
-                    Some(piece) => {
-                        match piece.role {
-                            Role::King => "K ",
-                            Role::Queen => "Q ",
-                            Role::Rook => "R ",
-                            Role::Bishop => "B ",
-                            Role::Knight => "Kn",
-                            Role::Pawn => "P "
-                        }
-                    },
+                    Some(piece) => piece.role.info().symbol,
+                    None if self.duck.as_ref() == Some(&Position { row: x, column: y }) => "D ",
                     None => "* "
                 };
 
                 // Adds unicode character
-                output.push_str(&String::from(format!("{} ", piece)));
+                output.push_str(&format!("{} ", piece));
             }
 
             // New line
-            output.push_str(&String::from(format!("\n")));
+            output.push('\n');
         }
         write!(f, "{}", output)
     }
@@ -306,8 +577,12 @@ impl fmt::Debug for Game {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::Game;
     use super::gamestate::GameState;
+    use super::engine::{iterative_deepening, Bound, Limits, SearchControl, TranspositionTable};
+    use super::{chess960_back_rank, Move, Tactic, Bughouse, BughouseBoard, BughouseTeam, Color, FourPlayerGame, FourPlayerMode, Player, MoveError, Movement, Position, Role, ROLES, SetupBuilder, SetupError, Variant};
 
     // check test framework
     #[test]
@@ -348,4 +623,775 @@ mod tests {
 
         assert_eq!(game.get_game_state(), GameState::InProgress);
     }
-}
\ No newline at end of file
+
+    // check FEN export after a pawn move
+    #[test]
+    fn fen_after_first_move() {
+        let mut game = Game::new();
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        game.make_move("e2".to_string(), "e4".to_string());
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+    }
+
+    // check that moves are recorded and numbered, and resigning ends the game
+    #[test]
+    fn pgn_after_resign() {
+        let mut game = Game::new();
+        game.make_move("e2".to_string(), "e4".to_string());
+        game.make_move("d7".to_string(), "d5".to_string());
+        game.make_move("e4".to_string(), "d5".to_string());
+        game.make_move("g8".to_string(), "f6".to_string());
+
+        assert_eq!(game.get_history(), vec!["e4", "d5", "exd5", "Nf6"]);
+        assert_eq!(game.resign(Color::White), GameState::GameOver);
+        assert_eq!(game.get_winner(), Some(Color::Black));
+        assert!(game.to_pgn().ends_with("1. e4 d5 2. exd5 Nf6 0-1\n"));
+
+        // numbering follows the start position, black moving first
+        let mut game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 b - - 0 12").unwrap();
+        game.try_make_move("e8", "e7").unwrap();
+        game.try_make_move("e1", "e2").unwrap();
+        game.try_make_move("e7", "e6").unwrap();
+        assert!(game.to_pgn().ends_with("\n12... Ke7 13. Ke2 Ke6 *\n"));
+    }
+
+    // check that sliders stop at the first piece in their way
+    #[test]
+    fn blocked_moves() {
+        let game = Game::new();
+        assert_eq!(game.get_possible_moves("c1".to_string()), Some(Vec::new()));
+
+        let moves = game.get_possible_moves("e2".to_string()).unwrap();
+        assert_eq!(moves.len(), 2);
+    }
+
+    // check that every opening move is listed once
+    #[test]
+    fn legal_moves_at_start() {
+        let game = Game::new();
+        let moves = game.get_legal_moves();
+
+        assert_eq!(moves.len(), 20);
+        assert_eq!(moves[0].0.to_string(), "b1");
+    }
+
+    // check the numbering of start positions and that every one is a valid Chess960 back rank
+    #[test]
+    fn chess960_back_ranks() {
+        let standard = Game::new_chess960(518).unwrap();
+        assert_eq!(standard.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(Game::new_chess960(960).is_none());
+
+        let mut ranks = HashSet::new();
+        for index in 0..960 {
+            let rank = chess960_back_rank(index).unwrap();
+            let find = |role: Role| (0..8).filter(|column| rank[*column] == role).collect::<Vec<usize>>();
+
+            let (bishops, rooks, king) = (find(Role::Bishop), find(Role::Rook), find(Role::King));
+            assert_ne!(bishops[0] % 2, bishops[1] % 2);
+            assert!(rooks[0] < king[0] && king[0] < rooks[1]);
+            ranks.insert(rank);
+        }
+
+        // every number gives a back rank of its own
+        assert_eq!(ranks.len(), 960);
+        assert_eq!(Game::new_chess960_seeded(7).to_fen(), Game::new_chess960_seeded(7).to_fen());
+    }
+
+    // check castling on both sides of a standard game, with the king moving two squares or onto the rook
+    #[test]
+    fn standard_castling() {
+        let mut game = Game::from_fen("r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1").unwrap();
+        assert!(game.get_possible_moves("e1".to_string()).unwrap().contains(&Position::parse("h1").unwrap()));
+
+        game.make_move("e1".to_string(), "g1".to_string());
+        game.make_move("e8".to_string(), "a8".to_string());
+        assert_eq!(game.to_fen(), "2kr3r/pppppppp/8/8/8/8/PPPPPPPP/R4RK1 w - - 2 2");
+        assert_eq!(game.get_history(), vec!["O-O", "O-O-O"]);
+    }
+
+    // check Chess960 castling with the king next to its rook and X-FEN/Shredder-FEN round trips
+    #[test]
+    fn chess960_castling() {
+        let fen = "1r4kr/pppppppp/8/8/8/8/PPPPPPPP/1R4KR w KQkq - 0 1";
+        let shredder = "1r4kr/pppppppp/8/8/8/8/PPPPPPPP/1R4KR w HBhb - 0 1";
+        assert_eq!(Game::from_fen(fen).unwrap().to_shredder_fen(), shredder);
+
+        let mut game = Game::from_fen(shredder).unwrap();
+        assert!(game.is_chess960());
+        assert_eq!(game.to_fen(), fen);
+
+        // the king stays on g1 and the rook jumps over to f1
+        game.make_move("g1".to_string(), "h1".to_string());
+        assert_eq!(game.to_fen(), "1r4kr/pppppppp/8/8/8/8/PPPPPPPP/1R3RK1 b kq - 1 1");
+        assert!(game.to_pgn().contains("[Variant \"Chess960\"]"));
+
+        // the king passes the rook on its way to c8
+        game.make_move("g8".to_string(), "b8".to_string());
+        assert_eq!(game.to_fen(), "2kr3r/pppppppp/8/8/8/8/PPPPPPPP/1R3RK1 w - - 2 2");
+    }
+
+    // check that set up positions are validated before a game starts
+    #[test]
+    fn setup_validation() {
+        let square = |name: &str| Position::parse(name).unwrap();
+        let drill = SetupBuilder::new()
+            .place(square("e1"), Color::White, Role::King)
+            .place(square("e8"), Color::Black, Role::King)
+            .place(square("a7"), Color::White, Role::Pawn);
+
+        let game = drill.clone().active_color(Color::Black).build().unwrap();
+        assert_eq!(game.to_fen(), "4k3/P7/8/8/8/8/8/4K3 b - - 0 1");
+
+        assert_eq!(drill.clone().place(square("a8"), Color::Black, Role::Pawn).build().err(), Some(SetupError::PawnOnBackRank));
+        assert_eq!(drill.clone().remove(&square("e8")).build().err(), Some(SetupError::MissingKing));
+        assert_eq!(drill.clone().place(square("d1"), Color::White, Role::King).build().err(), Some(SetupError::TooManyKings));
+        assert_eq!(drill.castling("K").build().err(), Some(SetupError::InvalidCastling));
+    }
+
+    // check that a set up game exports its start position and move numbers to PGN
+    #[test]
+    fn setup_to_pgn() {
+        let square = |name: &str| Position::parse(name).unwrap();
+        let setup = SetupBuilder::new()
+            .place(square("e1"), Color::White, Role::King)
+            .place(square("e8"), Color::Black, Role::King)
+            .place(square("a7"), Color::White, Role::Pawn)
+            .active_color(Color::Black)
+            .counters(3, 30);
+
+        let mut game = setup.build().unwrap();
+        game.try_make_move("e8", "d7").unwrap();
+        game.try_make_move("a7", "a8").unwrap();
+        game.try_set_promotion("a8", Role::Queen).unwrap();
+        let pgn = game.to_pgn();
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/P7/8/8/8/8/8/4K3 b - - 3 30\"]\n"));
+        assert!(pgn.ends_with("\n30... Kd7 31. a8=Q *\n"));
+
+        // the FEN header starts the same game again
+        let fen = pgn.lines().find(|line| line.starts_with("[FEN")).unwrap();
+        let fen = &fen[6..fen.len() - 2];
+        assert_eq!(Game::from_fen(fen).unwrap().to_fen(), setup.build().unwrap().to_fen());
+    }
+
+    // check queen odds, white keeps castling rights
+    #[test]
+    fn setup_odds_game() {
+        let game = SetupBuilder::from_game(&Game::new()).remove(&Position::parse("d1").unwrap()).build().unwrap();
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1");
+        assert!(game.to_pgn().contains("[SetUp \"1\"]"));
+        assert!(!game.is_chess960());
+    }
+
+    // check that an atomic capture next to the king blows it up, pawns survive
+    #[test]
+    fn atomic_explosion() {
+        let square = |name: &str| Position::parse(name).unwrap();
+        let mut game = SetupBuilder::new()
+            .variant(Variant::Atomic)
+            .place(square("e1"), Color::White, Role::King)
+            .place(square("d1"), Color::White, Role::Rook)
+            .place(square("e8"), Color::Black, Role::King)
+            .place(square("d7"), Color::Black, Role::Knight)
+            .place(square("c7"), Color::Black, Role::Pawn)
+            .build()
+            .unwrap();
+
+        assert_eq!(game.make_move("d1".to_string(), "d7".to_string()), Some(GameState::GameOver));
+        assert_eq!(game.to_fen(), "8/2p5/8/8/8/8/8/4K3 b - - 0 1");
+        assert_eq!(game.get_winner(), Some(Color::White));
+        assert!(game.to_pgn().contains("[Variant \"Atomic\"]"));
+    }
+
+    // check that neither the king nor its neighbours may capture next to it
+    #[test]
+    fn atomic_own_king_is_safe() {
+        let square = |name: &str| Position::parse(name).unwrap();
+        let game = SetupBuilder::new()
+            .variant(Variant::Atomic)
+            .place(square("e1"), Color::White, Role::King)
+            .place(square("d5"), Color::White, Role::Rook)
+            .place(square("e8"), Color::Black, Role::King)
+            .place(square("d2"), Color::Black, Role::Knight)
+            .build()
+            .unwrap();
+
+        assert!(!game.get_possible_moves("e1".to_string()).unwrap().contains(&square("d2")));
+        assert!(!game.get_possible_moves("d5".to_string()).unwrap().contains(&square("d2")));
+        assert!(game.get_possible_moves("d5".to_string()).unwrap().contains(&square("d3")));
+    }
+
+    // check that captures are forced in antichess and losing every piece wins
+    #[test]
+    fn antichess_forced_captures() {
+        let mut game = Game::new_variant(Variant::Antichess);
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1");
+
+        game.make_move("e2".to_string(), "e4".to_string());
+        game.make_move("d7".to_string(), "d5".to_string());
+        assert_eq!(game.get_legal_moves().len(), 1);
+        assert_eq!(game.try_make_move("g1", "f3"), Err(MoveError::IllegalMove));
+
+        let square = |name: &str| Position::parse(name).unwrap();
+        let mut ending = SetupBuilder::new()
+            .variant(Variant::Antichess)
+            .place(square("a2"), Color::White, Role::Pawn)
+            .place(square("b3"), Color::Black, Role::Bishop)
+            .build()
+            .unwrap();
+        assert_eq!(ending.make_move("a2".to_string(), "b3".to_string()), Some(GameState::GameOver));
+        assert_eq!(ending.get_winner(), Some(Color::Black));
+    }
+
+    // check that the king is an ordinary piece a pawn may become in antichess
+    #[test]
+    fn antichess_promotion_to_king() {
+        let square = |name: &str| Position::parse(name).unwrap();
+        let mut game = SetupBuilder::new()
+            .variant(Variant::Antichess)
+            .place(square("a7"), Color::White, Role::Pawn)
+            .place(square("h2"), Color::Black, Role::Pawn)
+            .build()
+            .unwrap();
+
+        assert_eq!(game.make_move("a7".to_string(), "a8".to_string()), Some(GameState::Promotion));
+        assert_eq!(game.try_set_promotion("a8", Role::King), Ok(GameState::InProgress));
+        assert_eq!(game.get_piece_at(&square("a8")), Some((Color::White, Role::King)));
+    }
+
+    // check that a king reaching the center wins King of the Hill
+    #[test]
+    fn king_of_the_hill() {
+        let square = |name: &str| Position::parse(name).unwrap();
+        let mut game = SetupBuilder::new()
+            .variant(Variant::KingOfTheHill)
+            .place(square("e3"), Color::White, Role::King)
+            .place(square("e8"), Color::Black, Role::King)
+            .place(square("a8"), Color::Black, Role::Rook)
+            .active_color(Color::Black)
+            .build()
+            .unwrap();
+
+        assert_eq!(game.make_move("a8".to_string(), "a3".to_string()), Some(GameState::Check));
+        assert!(game.is_check());
+        assert_eq!(game.make_move("e3".to_string(), "d4".to_string()), Some(GameState::GameOver));
+        assert_eq!(game.get_winner(), Some(Color::White));
+    }
+
+    // check that Three-check counters are kept in FEN and the third check wins
+    #[test]
+    fn three_check() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 2+3 0 1").unwrap();
+        assert_eq!(game.get_variant(), Variant::ThreeCheck);
+        assert_eq!(game.get_checks(&Color::White), 1);
+        assert_eq!(Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +1+0").unwrap().to_fen(), game.to_fen());
+
+        game.make_move("a1".to_string(), "a8".to_string());
+        assert_eq!(game.to_fen(), "R3k3/8/8/8/8/8/8/4K3 b - - 1+3 1 1");
+        game.make_move("e8".to_string(), "e7".to_string());
+        assert_eq!(game.make_move("a8".to_string(), "a7".to_string()), Some(GameState::GameOver));
+        assert_eq!(game.get_winner(), Some(Color::White));
+    }
+
+    // check that captured pieces change sides and can be dropped in Crazyhouse
+    #[test]
+    fn crazyhouse_drops() {
+        let mut game = Game::new_variant(Variant::Crazyhouse);
+        game.make_move("e2".to_string(), "e4".to_string());
+        game.make_move("d7".to_string(), "d5".to_string());
+        game.make_move("e4".to_string(), "d5".to_string());
+        assert_eq!(game.get_pocket(&Color::White), vec![Role::Pawn]);
+        // black has nothing to drop yet
+        assert_eq!(game.try_drop(Role::Pawn, "e5"), Err(MoveError::IllegalMove));
+
+        game.make_move("d8".to_string(), "d5".to_string());
+        assert_eq!(game.try_drop(Role::Pawn, "e8"), Err(MoveError::IllegalMove));
+        assert_eq!(game.try_drop(Role::Pawn, "e6"), Ok(GameState::InProgress));
+        assert_eq!(game.to_fen(), "rnb1kbnr/ppp1pppp/4P3/3q4/8/8/PPPP1PPP/RNBQKBNR[p] b KQkq - 0 3");
+        assert_eq!(game.get_history(), vec!["e4", "d5", "exd5", "Qxd5", "P@e6"]);
+    }
+
+    // check that promoted pieces are kept in FEN and turn back into pawns when captured
+    #[test]
+    fn crazyhouse_promoted_pieces() {
+        let fen = "Q~3k3/8/8/8/8/8/8/r3K3[Nn] b - - 0 1";
+        let mut game = Game::from_fen(fen).unwrap();
+        assert_eq!(game.get_variant(), Variant::Crazyhouse);
+        assert_eq!(game.to_fen(), fen);
+
+        game.make_move("a1".to_string(), "a8".to_string());
+        assert_eq!(game.get_pocket(&Color::Black), vec![Role::Knight, Role::Pawn]);
+    }
+
+    // check the horde start, double steps from the first rank and that black wins by taking every pawn
+    #[test]
+    fn horde() {
+        let game = Game::new_variant(Variant::Horde);
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1");
+
+        let square = |name: &str| Position::parse(name).unwrap();
+        let mut ending = SetupBuilder::new()
+            .variant(Variant::Horde)
+            .place(square("a1"), Color::White, Role::Pawn)
+            .place(square("e8"), Color::Black, Role::King)
+            .place(square("a8"), Color::Black, Role::Rook)
+            .build()
+            .unwrap();
+        assert_eq!(ending.get_possible_moves("a1".to_string()), Some(vec![square("a2"), square("a3")]));
+
+        ending.make_move("a1".to_string(), "a3".to_string());
+        assert_eq!(ending.make_move("a8".to_string(), "a3".to_string()), Some(GameState::GameOver));
+        assert_eq!(ending.get_winner(), Some(Color::Black));
+    }
+
+    // check that racing kings forbids checks and the first king on the last rank wins
+    #[test]
+    fn racing_kings() {
+        let game = Game::new_variant(Variant::RacingKings);
+        // the knight on c3 would check the black king
+        assert!(!game.get_possible_moves("e2".to_string()).unwrap().contains(&Position::parse("c3").unwrap()));
+        assert_eq!(game.get_legal_moves().len(), 21);
+
+        let mut race = Game::from_fen_variant("8/6K1/8/8/8/8/k7/8 w - - 0 1", Variant::RacingKings).unwrap();
+        assert_eq!(race.make_move("g7".to_string(), "g8".to_string()), Some(GameState::GameOver));
+        assert_eq!(race.get_winner(), Some(Color::White));
+    }
+
+    // check that each side only sees its own pieces and where they can move
+    #[test]
+    fn fog_of_war() {
+        let mut game = Game::new();
+        let view = game.view_for(Color::White);
+        assert_eq!(view.visible_squares().len(), 32);
+        assert!(!view.is_visible(&Position::parse("e7").unwrap()));
+        assert_eq!(view.get_piece_at(&Position::parse("e1").unwrap()), Some((Color::White, Role::King)));
+        assert_eq!(view.to_string().lines().next(), Some("? ? ? ? ? ? ? ?"));
+
+        // the bishop sees along the opened diagonal up to the pawn on a6
+        game.make_move("e2".to_string(), "e4".to_string());
+        game.make_move("a7".to_string(), "a6".to_string());
+        let view = game.view_for(Color::White);
+        assert_eq!(view.get_piece_at(&Position::parse("a6").unwrap()), Some((Color::Black, Role::Pawn)));
+        assert!(!view.is_visible(&Position::parse("a5").unwrap()));
+        assert_eq!(game.view_for(Color::Black).get_piece_at(&Position::parse("e4").unwrap()), None);
+    }
+
+    // check that squares, pawn steps and fen follow the size of the board
+    #[test]
+    fn board_dimensions() {
+        let mut gardner = Game::new_variant(Variant::Gardner);
+        assert_eq!(gardner.to_fen(), "rnbqk/ppppp/5/PPPPP/RNBQK w - - 0 1");
+        assert_eq!(gardner.get_legal_moves().len(), 7);
+        assert_eq!(gardner.try_make_move("f2", "f3"), Err(MoveError::InvalidPosition));
+
+        let los_alamos = Game::new_variant(Variant::LosAlamos);
+        assert_eq!(los_alamos.get_possible_moves("a2".to_string()), Some(vec![Position::parse("a3").unwrap()]));
+
+        // grand chess pawns step twice from the third rank and promote on the tenth
+        let mut grand = Game::new_variant(Variant::Grand);
+        assert_eq!(grand.make_move("e3".to_string(), "e5".to_string()), Some(GameState::InProgress));
+        let mut promotion = Game::from_fen_variant("k9/4P5/10/10/10/10/10/10/10/K9 w - - 0 1", Variant::Grand).unwrap();
+        assert_eq!(promotion.make_move("e9".to_string(), "e10".to_string()), Some(GameState::Promotion));
+        assert_eq!(promotion.try_set_promotion("e10", Role::Chancellor), Ok(GameState::InProgress));
+        assert_eq!(promotion.to_fen(), "k3C5/10/10/10/10/10/10/10/10/K9 b - - 0 1");
+    }
+
+    // check the capablanca start and that the king castles three squares
+    #[test]
+    fn capablanca() {
+        let game = Game::new_variant(Variant::Capablanca);
+        assert_eq!(game.to_fen(), "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1");
+        assert_eq!(game.get_legal_moves().len(), 28);
+
+        let mut castling = Game::from_fen_variant("r4k3r/10/10/10/10/10/10/R4K3R w KQkq - 0 1", Variant::Capablanca).unwrap();
+        castling.make_move("f1".to_string(), "i1".to_string());
+        castling.make_move("f8".to_string(), "c8".to_string());
+        assert_eq!(castling.to_fen(), "2kr5r/10/10/10/10/10/10/R6RK1 w - - 2 2");
+    }
+
+    // check betza notation and the moves of riders and hoppers
+    #[test]
+    fn fairy_pieces() {
+        assert_eq!(Movement::parse("Q"), Movement::parse("RB"));
+        assert!(Movement::parse("mRcpR").is_some());
+        assert!(Movement::parse("xQ").is_none());
+        assert!(Movement::parse("fm").is_none());
+
+        let square = |name: &str| Position::parse(name).unwrap();
+        let game = SetupBuilder::new()
+            .place(square("a1"), Color::White, Role::King)
+            .place(square("h8"), Color::Black, Role::King)
+            .place(square("b1"), Color::White, Role::Nightrider)
+            .place(square("d1"), Color::White, Role::Grasshopper)
+            .place(square("d2"), Color::White, Role::Pawn)
+            .place(square("f3"), Color::Black, Role::Pawn)
+            .place(square("e5"), Color::White, Role::Amazon)
+            .build()
+            .unwrap();
+
+        let moves = |name: &str| {
+            let mut moves = game.get_possible_moves(name.to_string()).unwrap();
+            moves.sort();
+            return moves;
+        };
+        assert_eq!(moves("d1"), vec![square("d3"), square("g4")]);
+        // the nightrider is blocked by its own pawn on d2
+        assert_eq!(moves("b1"), vec![square("a3"), square("c3"), square("d5"), square("e7")]);
+        assert_eq!(moves("e5").len(), 34);
+        assert_eq!(game.to_fen(), "7k/8/8/4M3/8/5p2/3P4/KH1G4 w - - 0 1");
+
+        // one row per role in the order of Role, movements parsed once
+        assert!(ROLES.iter().enumerate().all(|(index, info)| info.role.index() == index));
+        assert_eq!(Role::from_char('h'), Some(Role::Nightrider));
+        assert!(std::ptr::eq(Role::Amazon.movement(), Role::Amazon.movement()));
+        assert_eq!(Role::Amazon.movement(), &Movement::parse("QN").unwrap());
+    }
+
+    // check that captures go to the partner and a captured king ends both boards
+    #[test]
+    fn bughouse() {
+        let mut bughouse = Bughouse::new();
+        bughouse.try_make_move(BughouseBoard::A, "e2", "e4").unwrap();
+        bughouse.try_make_move(BughouseBoard::A, "d7", "d5").unwrap();
+        bughouse.try_make_move(BughouseBoard::A, "e4", "d5").unwrap();
+        assert_eq!(bughouse.get_board(BughouseBoard::B).get_pocket(&Color::Black), vec![Role::Pawn]);
+        assert!(bughouse.get_board(BughouseBoard::A).get_pocket(&Color::White).is_empty());
+
+        bughouse.try_make_move(BughouseBoard::B, "e2", "e4").unwrap();
+        bughouse.try_drop(BughouseBoard::B, Role::Pawn, "e5").unwrap();
+
+        bughouse.try_make_move(BughouseBoard::A, "f7", "f6").unwrap();
+        bughouse.try_make_move(BughouseBoard::A, "d1", "h5").unwrap();
+        bughouse.try_make_move(BughouseBoard::A, "a7", "a6").unwrap();
+        assert_eq!(bughouse.try_make_move(BughouseBoard::A, "h5", "e8"), Ok(GameState::GameOver));
+        assert_eq!(bughouse.get_winner(), Some(BughouseTeam::A));
+        assert_eq!(bughouse.try_make_move(BughouseBoard::B, "d2", "d4"), Err(MoveError::GameOver));
+
+        let bpgn = bughouse.to_bpgn();
+        assert!(bpgn.contains("[Result \"1-0\"]"));
+        assert!(bpgn.ends_with("1A. e4 1a. d5 2A. exd5 1B. e4 1b. P@e5 2a. f6 3A. Qh5 3a. a6 4A. Qxe8 1-0\n"));
+    }
+
+    // check turn rotation on the cross board, players without moves and what a captured king does in either mode
+    #[test]
+    fn four_player() {
+        let opening = [("i2", "i4"), ("b7", "c7"), ("g13", "g12"), ("m6", "k6"), ("d2", "d3"), ("b8", "c8"), ("f13", "f12")];
+
+        let mut game = FourPlayerGame::new(FourPlayerMode::FreeForAll);
+        assert_eq!(game.get_legal_moves().len(), 20);
+        assert_eq!(game.to_string().lines().next(), Some("         yR yN yB yK yQ yB yN yR"));
+        assert_eq!(game.try_make_move("b7", "c7"), Err(MoveError::NotYourTurn));
+        assert_eq!(game.try_make_move("a1", "a2"), Err(MoveError::InvalidPosition));
+
+        for (from, to) in opening.iter() {
+            game.try_make_move(from, to).unwrap();
+        }
+        assert_eq!(game.get_active_player(), Player::Green);
+
+        // the green queen takes the red king, red is out and blue moves next
+        assert_eq!(game.try_make_move("n7", "h1"), Ok(GameState::InProgress));
+        assert_eq!(game.get_points(Player::Green), 20);
+        assert!(game.is_eliminated(Player::Red));
+        assert_eq!(game.get_piece_at(&Position::parse("d1").unwrap()), None);
+        assert_eq!(game.get_active_player(), Player::Blue);
+        assert_eq!(game.get_history().last(), Some(&"Qn7xh1".to_string()));
+
+        // in team games the first king decides
+        let mut teams = FourPlayerGame::new(FourPlayerMode::Teams);
+        for (from, to) in opening.iter() {
+            teams.try_make_move(from, to).unwrap();
+        }
+        assert_eq!(teams.try_make_move("n7", "h1"), Ok(GameState::GameOver));
+        assert_eq!(teams.get_winners(), vec![Player::Blue, Player::Green]);
+
+        // red's last pawn is blocked, so red is out when the turn comes, or passed over in teams
+        let pieces = [("d2", Player::Red, Role::Pawn), ("d3", Player::Yellow, Role::Knight), ("a7", Player::Blue, Role::King), ("h14", Player::Yellow, Role::King), ("n7", Player::Green, Role::King)];
+        let mut game = FourPlayerGame::with_pieces(FourPlayerMode::FreeForAll, Player::Green, &pieces);
+        assert_eq!(game.try_make_move("n7", "n8"), Ok(GameState::InProgress));
+        assert!(game.is_eliminated(Player::Red));
+        assert_eq!(game.get_piece_at(&Position::parse("d2").unwrap()), None);
+        assert_eq!(game.get_active_player(), Player::Blue);
+
+        let mut teams = FourPlayerGame::with_pieces(FourPlayerMode::Teams, Player::Green, &pieces);
+        teams.try_make_move("n7", "n8").unwrap();
+        assert!(!teams.is_eliminated(Player::Red));
+        assert_eq!(teams.get_active_player(), Player::Blue);
+    }
+
+    // check the duck has to move after every move, blocks pieces and shows up in notation and fen
+    #[test]
+    fn duck_chess() {
+        let mut game = Game::new_variant(Variant::Duck);
+        assert_eq!(game.try_make_move("e2", "e4"), Ok(GameState::DuckPlacement));
+        assert_eq!(game.get_active_color(), Color::White);
+        assert_eq!(game.try_make_move("e7", "e5"), Err(MoveError::DuckPending));
+        assert_eq!(game.try_place_duck("e4"), Err(MoveError::IllegalMove));
+        assert_eq!(game.try_place_duck("c4"), Ok(GameState::InProgress));
+
+        // the duck blocks the bishop and cannot be captured
+        let mut bishop = game.get_possible_moves("f1".to_string()).unwrap();
+        bishop.sort();
+        assert_eq!(bishop, vec![Position::parse("e2").unwrap(), Position::parse("d3").unwrap()]);
+        assert!(game.to_fen().starts_with("rnbqkbnr/pppppppp/8/8/2*1P3/8/PPPP1PPP/RNBQKBNR b"));
+
+        // the duck has to leave its square
+        game.try_make_move("e7", "e5").unwrap();
+        assert_eq!(game.try_place_duck("c4"), Err(MoveError::IllegalMove));
+        game.try_place_duck("h4").unwrap();
+        assert_eq!(game.get_history(), vec!["e4@c4".to_string(), "e5@h4".to_string()]);
+
+        let restored = Game::from_fen(&game.to_fen()).unwrap();
+        assert_eq!(restored.get_variant(), Variant::Duck);
+        assert_eq!(restored.get_duck(), Position::parse("h4"));
+
+        // capturing the king ends the game at once
+        let mut game = Game::from_fen("4k3/8/8/8/3*4/8/8/4Q1K1 w - - 0 1").unwrap();
+        assert_eq!(game.try_make_move("e1", "e8"), Ok(GameState::GameOver));
+        assert_eq!(game.get_winner(), Some(Color::White));
+    }
+
+    // check attackers, defended squares and that pawns only attack diagonally
+    #[test]
+    fn attack_maps() {
+        let square = |name: &str| Position::parse(name).unwrap();
+
+        let mut game = Game::new();
+        assert_eq!(game.attackers(&square("f3"), Color::White), vec![square("g1"), square("e2"), square("g2")]);
+        assert_eq!(game.attack_map(Color::White).count(), 22);
+        assert!(!game.is_attacked(&square("e4"), Color::Black));
+
+        game.make_move("e2".to_string(), "e4".to_string());
+        game.make_move("d7".to_string(), "d5".to_string());
+        assert!(!game.is_attacked(&square("e5"), Color::White));
+        assert_eq!(game.attackers(&square("d5"), Color::White), vec![square("e4")]);
+        assert_eq!(game.attackers(&square("d5"), Color::Black), vec![square("d8")]);
+
+        let map = game.attack_map(Color::White);
+        assert!(map.contains(&square("h5")) && !map.contains(&square("h6")));
+        assert_eq!(map.bits() & 0xff, 0x7e);
+        assert_eq!(map.squares().len() as u32, map.count());
+    }
+
+    // check the assist flags moves hanging the king and plays them only when confirmed
+    #[test]
+    fn king_danger_assist() {
+        let square = |name: &str| Position::parse(name).unwrap();
+
+        let mut game = Game::new();
+        game.set_king_danger_assist(true);
+        game.try_make_move("f2", "f3").unwrap();
+        game.try_make_move("e7", "e6").unwrap();
+        game.try_make_move("a2", "a3").unwrap();
+        game.try_make_move("d8", "h4").unwrap();
+
+        // only g3 blocks the queen on h4
+        assert_eq!(game.get_annotated_moves("g2".to_string()), Some(vec![(square("g3"), false), (square("g4"), true)]));
+        assert_eq!(game.get_annotated_moves("h4".to_string()), None);
+        assert_eq!(game.try_make_move("g2", "g4"), Err(MoveError::SuicidalMove));
+        assert_eq!(game.get_active_color(), Color::White);
+        assert_eq!(game.try_make_move_confirmed("g2", "g4", true), Ok(GameState::InProgress));
+
+        // winning the game is never a danger
+        assert_eq!(game.get_annotated_moves("h4".to_string()).unwrap().iter().find(|(to, _)| *to == square("e1")), Some(&(square("e1"), false)));
+        assert_eq!(game.try_make_move("h4", "e1"), Ok(GameState::GameOver));
+    }
+
+    // check each motif in a position made for it
+    #[test]
+    fn tactics() {
+        let square = |name: &str| Position::parse(name).unwrap();
+        let tactics = |fen: &str| Game::from_fen(fen).unwrap().tactics(Color::White);
+
+        assert!(tactics("4k3/8/2n5/1B6/8/8/8/4K3 w - - 0 1").contains(&Tactic::Pin { pinner: square("b5"), pinned: square("c6"), behind: square("e8"), absolute: true }));
+        assert!(tactics("4q3/8/4k3/8/8/8/8/K3R3 w - - 0 1").contains(&Tactic::Skewer { attacker: square("e1"), front: square("e6"), behind: square("e8") }));
+        assert!(tactics("r3k3/2N5/8/8/8/8/8/4K3 w - - 0 1").contains(&Tactic::Fork { forker: square("c7"), targets: vec![square("a8"), square("e8")] }));
+        assert!(tactics("q3k3/8/8/8/N7/8/8/R3K3 w - - 0 1").contains(&Tactic::DiscoveredAttack { mover: square("a4"), attacker: square("a1"), target: square("a8") }));
+
+        // the queen alone guards both knights, and the rook on d1 pins one of them to her
+        let found = tactics("3q3k/8/8/n2n4/8/8/8/R2R3K w - - 0 1");
+        assert!(found.contains(&Tactic::Overloaded { defender: square("d8"), defended: vec![square("a5"), square("d5")] }));
+        assert!(found.contains(&Tactic::Pin { pinner: square("d1"), pinned: square("d5"), behind: square("d8"), absolute: false }));
+        assert!(!found.iter().any(|tactic| matches!(tactic, Tactic::Hanging { .. })));
+
+        assert!(tactics("4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1").contains(&Tactic::Hanging { piece: square("d5"), attackers: vec![square("d1")] }));
+    }
+
+    // check exchanges with defenders, x-rays behind rooks, promotions and captured kings
+    #[test]
+    fn static_exchange() {
+        let see = |fen: &str, uci: &str| Game::from_fen(fen).unwrap().see(&Move::parse(uci).unwrap());
+
+        assert_eq!(see("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5"), 100);
+        assert_eq!(see("4k3/8/4p3/3p4/4P3/8/8/4K3 w - - 0 1", "e4d5"), 0);
+        assert_eq!(see("4k3/8/4p3/3p4/8/8/8/3RK3 w - - 0 1", "d1d5"), -400);
+
+        // the second rook only joins behind the first one
+        assert_eq!(see("3rk3/8/8/3p4/8/8/3R4/4K3 w - - 0 1", "d2d5"), -400);
+        assert_eq!(see("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5"), 100);
+
+        // a pawn taking back on the last rank comes back as a queen
+        assert_eq!(see("r2N4/2P5/8/8/8/8/8/k3K3 b - - 0 1", "a8d8"), -1000);
+
+        // taking the king ends the exchange, a king taking a defended piece loses
+        assert_eq!(see("3qk3/8/8/8/8/8/8/K3R3 w - - 0 1", "e1e8"), Role::King.value());
+        assert_eq!(see("4k3/3P4/8/8/8/8/8/3RK3 b - - 0 1", "e8d7"), 100 - Role::King.value());
+
+        assert_eq!(Move::parse("e7e8q").unwrap().to_string(), "e7e8q");
+        assert_eq!(Move::parse("a10b10"), Some(Move::new(Position::parse("a10").unwrap(), Position::parse("b10").unwrap())));
+    }
+
+    // check zobrist hashes of transpositions and the replacement and aging of table entries
+    #[test]
+    fn transposition_table() {
+        let mut game = Game::new();
+        for (from, to) in [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")].iter() {
+            game.try_make_move(from, to).unwrap();
+        }
+        assert_eq!(game.zobrist_hash(), Game::new().zobrist_hash());
+        game.try_make_move("e2", "e4").unwrap();
+        assert_ne!(game.zobrist_hash(), Game::new().zobrist_hash());
+        assert_ne!(Game::new_variant(Variant::Crazyhouse).zobrist_hash(), Game::new().zobrist_hash());
+
+        // kept up to date move by move, debug builds check it against the whole board as well
+        let mut game = Game::from_fen("r3k3/1P6/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+        game.try_make_move("e1", "g1").unwrap();
+        game.try_make_move("a8", "a1").unwrap();
+        assert_eq!(game.zobrist_hash(), Game::from_fen(&game.to_fen()).unwrap().zobrist_hash());
+        let before = game.zobrist_hash();
+        game.try_make_move("b7", "b8").unwrap();
+        game.try_set_promotion("b8", Role::Queen).unwrap();
+        assert_ne!(game.zobrist_hash(), before);
+        let mut game = Game::from_fen_variant("4k3/5n2/4b3/3p4/3N4/8/8/4K3 w - - 0 1", Variant::Atomic).unwrap();
+        game.try_make_move("d4", "e6").unwrap();
+        game.try_make_move("e8", "d8").unwrap();
+        assert_eq!(game.get_piece_at(&Position::parse("f7").unwrap()), None);
+        assert_eq!(game.zobrist_hash(), Game::from_fen_variant(&game.to_fen(), Variant::Atomic).unwrap().zobrist_hash());
+
+        // one slot, so every position competes for it
+        let table = TranspositionTable::new(0);
+        let best = Move::parse("e7e8q");
+        table.store(1, 4, 35, Bound::Exact, best.clone());
+        assert_eq!(table.probe(1).map(|entry| (entry.depth, entry.score, entry.bound, entry.best_move)), Some((4, 35, Bound::Exact, best.clone())));
+
+        table.store(2, 3, -20, Bound::Lower, None);
+        assert_eq!(table.probe(2), None);
+        assert_eq!(table.probe(1).unwrap().best_move, best);
+
+        table.new_search();
+        table.store(2, 3, -20, Bound::Upper, None);
+        assert_eq!(table.probe(1), None);
+        assert_eq!(table.probe(2).unwrap().score, -20);
+        assert_eq!(table.hashfull(), 1000);
+
+        // the first depth counts even when the search is stopped right away
+        let control = SearchControl::new(Limits::depth(5));
+        assert_eq!(iterative_deepening(&control, Some), Some(5));
+        control.stop();
+        assert_eq!(iterative_deepening(&control, Some), Some(1));
+    }
+
+    // check the search finds king captures, single-thread searches repeat and threads and Hash are set through UCI
+    #[test]
+    fn lazy_smp_search() {
+        use super::engine::{uci_score, Engine, Uci, WIN_SCORE};
+        use std::io::Write;
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = Engine::new();
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4QK2 w - - 0 1").unwrap();
+        let result = engine.search(&game, Limits::depth(3));
+        assert_eq!(result.best_move, Move::parse("e1e8"));
+        assert_eq!(result.score, WIN_SCORE - 1);
+        assert_eq!(uci_score(result.score), "mate 1");
+
+        // the same search with one thread visits the same nodes
+        engine.new_game();
+        let first = engine.search(&Game::new(), Limits::depth(3));
+        engine.new_game();
+        assert_eq!(engine.search(&Game::new(), Limits::depth(3)), first);
+
+        engine.set_threads(4);
+        let result = engine.search(&Game::new(), Limits::depth(3));
+        let best = result.best_move.unwrap();
+        assert!(Game::new().get_legal_moves().contains(&(best.from, best.to)));
+
+        // answers go to a buffer
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+        impl Write for Buffer {
+            fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                return Ok(bytes.len());
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                return Ok(());
+            }
+        }
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let mut uci = Uci::new(Box::new(Buffer(output.clone())));
+        assert!(uci.handle("uci"));
+        let text = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        assert!(text.contains("option name Threads type spin") && text.contains("option name UCI_Chess960 type check"));
+        assert!(text.ends_with("uciok\n"));
+        assert!(uci.handle("setoption name Threads value 2"));
+        assert!(uci.handle("setoption name Hash value 1"));
+        assert_eq!((uci.get_engine().get_threads(), uci.get_engine().get_hash()), (2, 1));
+        assert!(uci.handle("position startpos moves e2e4 e7e5"));
+
+        // the search runs on its own thread, wait for it as a GUI would
+        let answer = |uci: &mut Uci, command: &str| -> String {
+            output.lock().unwrap().clear();
+            uci.handle(command);
+            while !String::from_utf8_lossy(&output.lock().unwrap()).contains("bestmove") {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            return String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        };
+        let text = answer(&mut uci, "go depth 2");
+        assert!(text.contains("info depth 2 multipv 1 score cp"));
+        assert!(text.lines().last().unwrap().starts_with("bestmove "));
+
+        // castling goes out as the king moving to its castling square, unless UCI_Chess960 is set
+        assert!(uci.handle("setoption name MultiPV value 256"));
+        assert!(uci.handle("position fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1"));
+        let text = answer(&mut uci, "go depth 1");
+        assert!(text.contains("pv e1g1") && !text.contains("e1h1"));
+        assert!(uci.handle("setoption name UCI_Chess960 value true"));
+        let text = answer(&mut uci, "go depth 1");
+        assert!(text.contains("pv e1h1") && !text.contains("e1g1"));
+        assert!(!uci.handle("quit"));
+    }
+
+    // check multipv lines are ordered, distinct and streamed depth by depth with their variations in notation
+    #[test]
+    fn multipv_analysis() {
+        use super::engine::{analyse, Engine, Score};
+
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4QK2 w - - 0 1").unwrap();
+        let mut depths = Vec::new();
+        let engine = Engine::new();
+        let control = SearchControl::new(Limits::depth(3));
+        let lines = engine.analyse_with(&game, &control, 3, &mut |lines| depths.push((lines[0].depth, lines.len())));
+        assert_eq!(depths, vec![(1, 3), (2, 3), (3, 3)]);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].score, Score::KingCapture(1));
+        assert_eq!(lines[0].score.to_string(), "#1");
+        assert_eq!((lines[0].get_move(), lines[0].san[0].as_str()), (&Move::parse("e1e8").unwrap(), "Qxe8"));
+        assert!(lines[1].get_move() != lines[0].get_move() && lines[2].get_move() != lines[1].get_move());
+        assert!(matches!(lines[1].score, Score::Centipawns(_)));
+
+        // full variations as moves and notation
+        let lines = analyse(&Game::new(), Limits::depth(3), 2);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(Score::from_search(35).to_string(), "+0.35");
+        for line in lines.iter() {
+            assert_eq!((line.depth, line.pv.len(), line.san.len()), (3, 3, 3));
+        }
+
+        // no more lines than moves
+        let game = Game::from_fen("k7/8/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        assert_eq!(analyse(&game, Limits::depth(2), 5).len(), 3);
+    }
+}
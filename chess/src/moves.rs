@@ -0,0 +1,66 @@
+use std::fmt;
+
+use crate::{Game, GameState, MoveError, Position, Role};
+
+/// A move of a piece from one square to another, with the role a pawn
+/// promotes to. Castling is the king moving onto its own rook.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub from: Position,
+    pub to: Position,
+    pub promotion: Option<Role>
+}
+
+impl Move {
+
+    /// A move without promotion.
+    pub fn new(from: Position, to: Position) -> Move {
+        return Move { from, to, promotion: None };
+    }
+
+    /// Read a move in coordinate notation, e.g. "e2e4" or "e7e8q".
+    pub fn parse(_move: &str) -> Option<Move> {
+        if !_move.is_ascii() {
+            return None;
+        }
+
+        // The target square starts at the second file letter
+        let split: usize = _move.char_indices().skip(1).find(|(_, letter)| letter.is_ascii_lowercase())?.0;
+        let from: Position = Position::parse(&_move[..split])?;
+        let rest: &str = &_move[split..];
+
+        // A trailing letter names the promotion
+        let (to, promotion): (&str, Option<Role>) = match rest.chars().last() {
+            Some(letter) if letter.is_ascii_lowercase() && rest.len() > 2 => (&rest[..rest.len() - 1], Some(Role::from_char(letter)?)),
+            _ => (rest, None)
+        };
+
+        return Some(Move { from, to: Position::parse(to)?, promotion });
+    }
+}
+
+impl Game {
+
+    /// Make a move together with its promotion, a pawn reaching the last rank without
+    /// one becomes a queen. Returns the resulting state of the game.
+    pub fn try_play(&mut self, _move: &Move) -> Result<GameState, MoveError> {
+        let to: String = _move.to.to_string();
+        let state: GameState = self.try_make_move(&_move.from.to_string(), &to)?;
+
+        if state == GameState::Promotion {
+            let role: Role = _move.promotion.clone().unwrap_or(Role::Queen);
+            return self.try_set_promotion(&to, role);
+        }
+        return Ok(state);
+    }
+}
+
+// Coordinate notation, e.g. "e7e8q"
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.promotion {
+            Some(role) => write!(f, "{}{}{}", self.from, self.to, role.to_char().to_ascii_lowercase()),
+            None => write!(f, "{}{}", self.from, self.to)
+        }
+    }
+}
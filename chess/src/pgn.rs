@@ -0,0 +1,119 @@
+use crate::{Color, Game, GameState, Position, Role, Variant};
+
+// Start position of a standard game
+const STANDARD_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+impl Game {
+
+    /// Get the moves played so far in standard algebraic notation.
+    pub fn get_history(&self) -> Vec<String> {
+        return self.history.clone();
+    }
+
+    /// Export the game in Portable Game Notation.
+    pub fn to_pgn(&self) -> String {
+        let result: &str = self.result();
+
+        // Seven tag roster
+        let mut pgn: String = String::new();
+        pgn.push_str("[Event \"Chess royale\"]\n");
+        pgn.push_str("[Site \"?\"]\n");
+        pgn.push_str("[Date \"????.??.??\"]\n");
+        pgn.push_str("[Round \"?\"]\n");
+        pgn.push_str("[White \"?\"]\n");
+        pgn.push_str("[Black \"?\"]\n");
+        pgn.push_str(&format!("[Result \"{}\"]\n", result));
+
+        // Games which did not start from the standard position
+        if self.variant != Variant::Standard {
+            pgn.push_str(&format!("[Variant \"{}\"]\n", self.variant));
+        } else if self.chess960 {
+            pgn.push_str("[Variant \"Chess960\"]\n");
+        }
+        if self.chess960 || self.start_fen != STANDARD_FEN {
+            pgn.push_str("[SetUp \"1\"]\n");
+            pgn.push_str(&format!("[FEN \"{}\"]\n", self.start_fen));
+        }
+        pgn.push('\n');
+
+        // Move text, numbered every white move from the move number of the start position.
+        // A first move by black gets its number as well, written "12... Ke7".
+        let fields: Vec<&str> = self.start_fen.split_whitespace().collect();
+        let black_first: bool = fields.get(1) == Some(&"b");
+        let first_number: usize = fields.get(5).and_then(|field| field.parse().ok()).unwrap_or(1);
+        for (index, notation) in self.history.iter().enumerate() {
+            let ply: usize = index + black_first as usize;
+            if ply.is_multiple_of(2) {
+                pgn.push_str(&format!("{}. ", first_number + ply / 2));
+            } else if index == 0 {
+                pgn.push_str(&format!("{}... ", first_number));
+            }
+            pgn.push_str(notation);
+            pgn.push(' ');
+        }
+        pgn.push_str(result);
+        pgn.push('\n');
+
+        return pgn;
+    }
+
+    // Game result as written in PGN
+    fn result(&self) -> &'static str {
+        if self.state != GameState::GameOver {
+            return "*";
+        }
+
+        match self.get_winner() {
+            Some(Color::White) => return "1-0",
+            Some(Color::Black) => return "0-1",
+            None => return "*"
+        }
+    }
+
+    // Algebraic notation of a legal move that is about to be played
+    pub(crate) fn move_notation(&self, from: &Position, to: &Position) -> String {
+        let piece = &self.board[from];
+        let capture: bool = self.board.contains_key(to);
+        let mut notation: String = String::new();
+
+        if piece.role == Role::Pawn {
+
+            // Pawn captures name the file the pawn came from
+            if capture {
+                notation.push(file(from));
+            }
+        } else {
+            notation.push(piece.role.to_char());
+
+            // Other pieces of the same kind which could reach the same square
+            let rivals: Vec<&Position> = self.board.values()
+                .filter(|other| other.color == piece.color && other.role == piece.role && other.position != *from)
+                .filter(|other| self.piece_moves(other).contains(to))
+                .map(|other| &other.position)
+                .collect();
+
+            // Disambiguate by file, then by rank, then by both
+            if !rivals.is_empty() {
+                if rivals.iter().all(|other| other.column != from.column) {
+                    notation.push(file(from));
+                } else if rivals.iter().all(|other| other.row != from.row) {
+                    notation.push_str(&from.row.to_string());
+                } else {
+                    notation.push_str(&from.to_string());
+                }
+            }
+        }
+
+        if capture {
+            notation.push('x');
+        }
+        notation.push_str(&to.to_string());
+
+        return notation;
+    }
+}
+
+// File letter of a position
+fn file(position: &Position) -> char {
+    return (b'a' + (position.column - 1) as u8) as char;
+}
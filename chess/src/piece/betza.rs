@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use super::color::Color;
+use super::dimensions::Dimensions;
+use super::position::Position;
+use super::Piece;
+
+/* Piece movement in Betza notation.
+ *
+ * Upper case letters are atoms, the jumps a piece can make in every
+ * direction:
+ *
+ *   W (1,0)  F (1,1)  D (2,0)  N (2,1)  A (2,2)
+ *   H (3,0)  C (3,1)  Z (3,2)  G (3,3)
+ *
+ * and the shorthands K = WF, R = WW, B = FF and Q = WWFF. A doubled atom
+ * rides, repeating the jump until it is blocked, e.g. NN for the
+ * nightrider. A number after an atom limits the steps, e.g. W2.
+ *
+ * Lower case letters in front of an atom change it:
+ *
+ *   m  only moves to empty squares      c  only captures
+ *   f  forward   b  backward   l  left   r  right
+ *   s  left and right          v  forward and backward
+ *   p  must hop over one piece first and may go on behind it
+ *   g  must hop over one piece and lands right behind it
+ *
+ * Directions are seen from the player, so forward is up for white, down
+ * for black and sideways for the players on the left and right in
+ * four-player chess. Several direction letters add up, "fs" is forward
+ * and sideways. A piece with more atoms moves like each of them, e.g. QN
+ * is the amazon.
+ */
+
+/// Movement of a piece, read from Betza notation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Movement {
+    atoms: Vec<Atom>
+}
+
+// One jump in every direction the modifiers allow
+#[derive(Clone, Debug, PartialEq)]
+struct Atom {
+    leap: (i8, i8),
+
+    // Steps along a direction, 1 for leapers and 0 for riders without limit
+    range: i8,
+
+    moves: bool,
+    captures: bool,
+    hop: Hop,
+
+    // Direction letters, none means every direction
+    directions: Vec<char>
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Hop {
+    None,
+    Cannon,
+    Grasshopper
+}
+
+impl Movement {
+
+    /// Read a movement from Betza notation, e.g. "QN" or "mfWcfF".
+    pub fn parse(_betza: &str) -> Option<Movement> {
+        let mut atoms: Vec<Atom> = Vec::new();
+        let letters: Vec<char> = _betza.chars().collect();
+        let mut index: usize = 0;
+
+        while index < letters.len() {
+
+            // Modifiers up to the next atom
+            let mut modifiers: Vec<char> = Vec::new();
+            while index < letters.len() && letters[index].is_ascii_lowercase() {
+                if !"mcfblrsvpg".contains(letters[index]) {
+                    return None;
+                }
+                modifiers.push(letters[index]);
+                index += 1;
+            }
+
+            let letter: char = *letters.get(index)?;
+            index += 1;
+
+            // A doubled atom rides, a number limits the steps
+            let mut range: i8 = 1;
+            if index < letters.len() && letters[index] == letter {
+                range = 0;
+                index += 1;
+            } else if index < letters.len() && letters[index].is_ascii_digit() {
+                range = letters[index].to_digit(10).unwrap() as i8;
+                index += 1;
+            }
+
+            // Shorthands for several atoms, the standard riders ride already
+            let leaps: Vec<((i8, i8), i8)> = match letter {
+                'K' => vec![((1, 0), range), ((1, 1), range)],
+                'R' => vec![((1, 0), 0)],
+                'B' => vec![((1, 1), 0)],
+                'Q' => vec![((1, 0), 0), ((1, 1), 0)],
+                _ => vec![(leap(letter)?, range)]
+            };
+
+            let moves: bool = modifiers.contains(&'m') || !modifiers.contains(&'c');
+            let captures: bool = modifiers.contains(&'c') || !modifiers.contains(&'m');
+            let hop: Hop = if modifiers.contains(&'g') {
+                Hop::Grasshopper
+            } else if modifiers.contains(&'p') {
+                Hop::Cannon
+            } else {
+                Hop::None
+            };
+            let directions: Vec<char> = modifiers.iter().filter(|modifier| "fblrsv".contains(**modifier)).cloned().collect();
+
+            for (leap, range) in leaps {
+                atoms.push(Atom { leap, range, moves, captures, hop, directions: directions.clone() });
+            }
+        }
+
+        if atoms.is_empty() {
+            return None;
+        }
+        return Some(Movement { atoms });
+    }
+
+    // Every square the piece can reach on the board, blocked squares count as friends
+    pub(crate) fn moves(&self, _piece: &Piece, board: &HashMap<Position, Piece>, dimensions: &Dimensions, blocked: &[Position]) -> Vec<Position> {
+
+        // White moves up the board, black down
+        let forward: (i8, i8) = match _piece.color {
+            Color::White => (0, 1),
+            Color::Black => (0, -1)
+        };
+
+        let occupant = |position: &Position| -> Option<bool> {
+            if blocked.contains(position) {
+                return Some(false);
+            }
+            match board.get(position) {
+                Some(other) => return Some(other.color != _piece.color),
+                None => return None
+            }
+        };
+        return self.targets(&_piece.position, forward, &occupant, &|position| dimensions.contains(position));
+    }
+
+    // Every square the piece attacks on the board, empty or not. Pieces of either
+    // color can be attacked, blocked squares cannot.
+    pub(crate) fn attacks(&self, _piece: &Piece, board: &HashMap<Position, Piece>, dimensions: &Dimensions, blocked: &[Position]) -> Vec<Position> {
+        let forward: (i8, i8) = match _piece.color {
+            Color::White => (0, 1),
+            Color::Black => (0, -1)
+        };
+
+        let occupant = |position: &Position| -> Option<bool> {
+            if blocked.contains(position) {
+                return Some(false);
+            }
+            match board.get(position) {
+                Some(_) => return Some(true),
+                None => return None
+            }
+        };
+
+        let mut attacks: Vec<Position> = Vec::new();
+        for atom in self.atoms.iter().filter(|atom| atom.captures) {
+            for direction in atom.directions_for(forward) {
+                for target in atom.targets(&_piece.position, direction, true, &occupant, &|position| dimensions.contains(position)) {
+                    if !attacks.contains(&target) {
+                        attacks.push(target);
+                    }
+                }
+            }
+        }
+
+        return attacks;
+    }
+
+    // Every square reachable from a square for a piece moving forward into the given
+    // direction. `occupant` tells if a square holds an enemy (true) or a friend (false),
+    // `on_board` whether a square exists, so boards of any shape and any number of
+    // players can share the rules.
+    pub(crate) fn targets(&self, origin: &Position, forward: (i8, i8), occupant: &dyn Fn(&Position) -> Option<bool>, on_board: &dyn Fn(&Position) -> bool) -> Vec<Position> {
+        let mut moves: Vec<Position> = Vec::new();
+
+        for atom in self.atoms.iter() {
+            for direction in atom.directions_for(forward) {
+                for target in atom.targets(origin, direction, false, occupant, on_board) {
+                    if !moves.contains(&target) {
+                        moves.push(target);
+                    }
+                }
+            }
+        }
+
+        return moves;
+    }
+}
+
+impl Atom {
+
+    // The up to eight directions of the leap the modifiers leave
+    fn directions_for(&self, forward: (i8, i8)) -> Vec<(i8, i8)> {
+        let (a, b) = self.leap;
+        let mut directions: Vec<(i8, i8)> = Vec::new();
+
+        for (column, row) in [(a, b), (b, a)].iter() {
+            for (column, row) in [(*column, *row), (-column, *row), (*column, -row), (-column, -row)].iter() {
+                if !directions.contains(&(*column, *row)) {
+                    directions.push((*column, *row));
+                }
+            }
+        }
+
+        if self.directions.is_empty() {
+            return directions;
+        }
+
+        // Right is forward turned clockwise, for white the column grows to the right
+        let right: (i8, i8) = (forward.1, -forward.0);
+
+        return directions.into_iter().filter(|(column, row)| {
+            let ahead: i8 = column * forward.0 + row * forward.1;
+            let aside: i8 = column * right.0 + row * right.1;
+            return self.directions.iter().any(|modifier| match modifier {
+                'f' => ahead > 0,
+                'b' => ahead < 0,
+                'l' => aside < 0,
+                'r' => aside > 0,
+                's' => aside != 0,
+                'v' => ahead != 0,
+                _ => false
+            });
+        }).collect();
+    }
+
+    // Squares reached going into one direction. Attacks count the empty squares
+    // the atom could capture on instead of those it could move to.
+    fn targets(&self, origin: &Position, direction: (i8, i8), attacks: bool, occupant: &dyn Fn(&Position) -> Option<bool>, on_board: &dyn Fn(&Position) -> bool) -> Vec<Position> {
+        let mut targets: Vec<Position> = Vec::new();
+        let mut hopped: bool = false;
+        let mut step: i8 = 1;
+
+        while self.range == 0 || step <= self.range {
+            let target: Position = Position {
+                row: origin.row + direction.1 * step,
+                column: origin.column + direction.0 * step
+            };
+            step += 1;
+
+            if !on_board(&target) {
+                break;
+            }
+
+            let enemy: Option<bool> = occupant(&target);
+
+            // Hoppers pass the first piece they meet
+            if self.hop != Hop::None && !hopped {
+                if enemy.is_some() {
+                    hopped = true;
+                }
+                continue;
+            }
+
+            match enemy {
+                Some(enemy) => {
+                    if self.captures && enemy {
+                        targets.push(target);
+                    }
+                    break;
+                },
+                None => {
+                    if (self.moves && !attacks) || (self.captures && attacks) {
+                        targets.push(target);
+                    }
+                }
+            }
+
+            // Grasshoppers land right behind the piece they hopped
+            if self.hop == Hop::Grasshopper {
+                break;
+            }
+        }
+
+        return targets;
+    }
+}
+
+// Jump of a single atom letter
+fn leap(letter: char) -> Option<(i8, i8)> {
+    match letter {
+        'W' => return Some((1, 0)),
+        'F' => return Some((1, 1)),
+        'D' => return Some((2, 0)),
+        'N' => return Some((2, 1)),
+        'A' => return Some((2, 2)),
+        'H' => return Some((3, 0)),
+        'C' => return Some((3, 1)),
+        'Z' => return Some((3, 2)),
+        'G' => return Some((3, 3)),
+        _ => return None
+    }
+}
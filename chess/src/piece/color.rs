@@ -2,4 +2,13 @@
 pub enum Color {
     White,
     Black
-}
\ No newline at end of file
+}
+impl Color {
+    // The color playing against this one
+    pub fn opponent(&self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::cmp;
+
+use super::position::Position;
+
+/// Number of files and ranks of a board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dimensions {
+    pub columns: i8,
+    pub rows: i8
+}
+
+impl Dimensions {
+
+    /// The 8x8 board of standard chess.
+    pub const STANDARD: Dimensions = Dimensions { columns: 8, rows: 8 };
+
+    /// Check if a position lies on the board.
+    pub fn contains(&self, _position: &Position) -> bool {
+        return _position.row >= 1 && _position.row <= self.rows && _position.column >= 1 && _position.column <= self.columns;
+    }
+
+    // Ranks on each side pawns may step twice from, the four middle ranks are left out.
+    // That is the first two ranks on 8x8, the first three on 10x10 and none on 5x5.
+    pub(crate) fn pawn_home_rows(&self) -> i8 {
+        return cmp::max(self.rows - 4, 0) / 2;
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Hash, Eq, PartialEq, Clone, Debug)]
+use std::fmt;
+
+use super::dimensions::Dimensions;
+
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Debug)]
 pub struct Position {
     pub row: i8, 
     pub column: i8 // Represented as a letter in String
@@ -6,72 +10,57 @@ pub struct Position {
 
 impl Position {
     pub fn new(_pos: String) -> Option<Self> {
+        let position: Option<Self> = Self::parse(&_pos);
+        if position.is_none() {
+            print!("Not a vaild position");
+        }
+        return position;
+    }
 
-        // Checks if String is a valid character length
-        if _pos.chars().count() == 2 {
-
-            // Creates variables
-            let mut _row: i8;
-            let mut _column: i8;
-
-            // Split String to characters
-            let chars: Vec<char> = _pos.chars().collect();
-
-            // Convert letter character into i8 (easier to use in code)
-            match chars[0] {
-                'a' => _column = 1,
-                'b' => _column = 2,
-                'c' => _column = 3,
-                'd' => _column = 4,
-                'e' => _column = 5,
-                'f' => _column = 6,
-                'g' => _column = 7,
-                'h' => _column = 8,
-                _ => {
-                    print!("Not a vaild position");
-                    return None;
-                }
-            };
+    // Same as new, without telling the user about invalid input.
+    // Files run from "a" to "z" and ranks from 1 to 99, the board of the
+    // game decides which of them exist.
+    pub fn parse(_pos: &str) -> Option<Self> {
 
-            // Convert character
-            match chars[1].to_digit(10) {
-                Some(value) => {
-                    if value > 8 || value < 1 {
-                        print!("Not a vaild position");
-                        return None;
-                    } else {
-                        _row = value as i8;
-                    };
-                },
-                None => {
-                    print!("Not a vaild position");
-                    return None;
-                }
-            };
+        // Checks if String is a valid character length
+        if _pos.len() < 2 || _pos.len() > 3 || !_pos.is_ascii() {
+            return None;
+        }
 
-            // Return position
-            return Some(Position {
-                row: _row,
-                column: _column
-            });
+        // Convert letter character into i8 (easier to use in code)
+        let letter: u8 = _pos.as_bytes()[0];
+        if !letter.is_ascii_lowercase() {
+            return None;
+        }
+        let _column: i8 = (letter - b'a' + 1) as i8;
 
-        } else {
-            print!("Not a vaild position");
+        // Convert the digits, without leading zeros
+        let digits: &str = &_pos[1..];
+        if digits.starts_with('0') || !digits.chars().all(|digit| digit.is_ascii_digit()) {
             return None;
         }
+        let _row: i8 = match digits.parse::<i8>() {
+            Ok(value) => value,
+            Err(_) => return None
+        };
+
+        // Return position
+        return Some(Position {
+            row: _row,
+            column: _column
+        });
     }
 
-    // Convert position back to String
-    pub fn to_string(self) -> String {
-        return format!("{}{}", self.row.to_string(), self.column.to_string());
+    // Check if position is within the standard 8x8 board, other boards use `Dimensions::contains`
+    pub fn is_valid(&self) -> bool {
+        return Dimensions::STANDARD.contains(self);
     }
+}
 
-    // Check if position is within board constraints
-    pub fn is_valid(self) -> bool {
-        if (self.row < 1 || self.row > 8 || self.column < 1 || self.column > 8) == true {
-            return false;
-        } else {
-            return true;
-        }
+// Convert position back to String, e.g. "e4"
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let column: char = (b'a' + (self.column - 1) as u8) as char;
+        write!(f, "{}{}", column, self.row)
     }
-}
\ No newline at end of file
+}
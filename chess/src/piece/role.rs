@@ -1,3 +1,15 @@
+use std::sync::OnceLock;
+
+use super::betza::Movement;
+
+/* Roles are declared in one table.
+ *
+ * Every role has one row in `ROLES` with its letter, how the board is
+ * rendered, its movement in Betza notation and its value, in the order of
+ * `Role`. Notation, rendering, hashing, the bindings and move generation
+ * all read the table, so a new piece is a new variant and a new row.
+ */
+
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 pub enum Role {
     King,
@@ -5,5 +17,95 @@ pub enum Role {
     Rook,
     Bishop,
     Knight,
-    Pawn
-}
\ No newline at end of file
+    Pawn,
+
+    // Bishop and knight, and rook and knight in one, for Capablanca and Grand chess
+    Archbishop,
+    Chancellor,
+
+    // Fairy pieces for custom setups: queen and knight in one, a knight
+    // repeating its jump, and a queen line hopper landing right behind a piece
+    Amazon,
+    Nightrider,
+    Grasshopper
+}
+
+/// What the rules know about a role.
+#[derive(Debug)]
+pub struct RoleInfo {
+    pub role: Role,
+
+    // Letter in FEN and algebraic notation (white case)
+    pub letter: char,
+
+    // Two characters wide, for the Debug output of `Game`
+    pub symbol: &'static str,
+
+    // Movement in Betza notation, see `Movement`
+    pub betza: &'static str,
+
+    // Material value in centipawns
+    pub value: i32
+}
+
+/// Every role in the order of `Role`.
+pub static ROLES: [RoleInfo; 11] = [
+    RoleInfo { role: Role::King, letter: 'K', symbol: "K ", betza: "K", value: 100000 },
+    RoleInfo { role: Role::Queen, letter: 'Q', symbol: "Q ", betza: "Q", value: 900 },
+    RoleInfo { role: Role::Rook, letter: 'R', symbol: "R ", betza: "R", value: 500 },
+    RoleInfo { role: Role::Bishop, letter: 'B', symbol: "B ", betza: "B", value: 300 },
+    RoleInfo { role: Role::Knight, letter: 'N', symbol: "Kn", betza: "N", value: 300 },
+    RoleInfo { role: Role::Pawn, letter: 'P', symbol: "P ", betza: "mfWcfF", value: 100 },
+    RoleInfo { role: Role::Archbishop, letter: 'A', symbol: "A ", betza: "BN", value: 700 },
+    RoleInfo { role: Role::Chancellor, letter: 'C', symbol: "C ", betza: "RN", value: 800 },
+    RoleInfo { role: Role::Amazon, letter: 'M', symbol: "M ", betza: "QN", value: 1200 },
+    RoleInfo { role: Role::Nightrider, letter: 'H', symbol: "H ", betza: "NN", value: 500 },
+    RoleInfo { role: Role::Grasshopper, letter: 'G', symbol: "G ", betza: "gQ", value: 200 }
+];
+
+// Movements of the roles, parsed from their Betza notation the first time one is needed
+static MOVEMENTS: OnceLock<Vec<Movement>> = OnceLock::new();
+
+impl Role {
+
+    /// Get the row of the role in `ROLES`.
+    pub fn info(&self) -> &'static RoleInfo {
+        return &ROLES[self.index()];
+    }
+
+    /// Get the position of the role in `ROLES`.
+    pub fn index(&self) -> usize {
+        return self.clone() as usize;
+    }
+
+    // Letter used for the role in FEN and algebraic notation (white case)
+    pub fn to_char(&self) -> char {
+        return self.info().letter;
+    }
+
+    // Role for a FEN or algebraic notation letter of either case
+    pub fn from_char(letter: char) -> Option<Role> {
+        let letter: char = letter.to_ascii_uppercase();
+        return ROLES.iter().find(|info| info.letter == letter).map(|info| info.role.clone());
+    }
+
+    /// Movement of the role in Betza notation, see `Movement`. Pawns may
+    /// also step twice from near their own side of the board.
+    pub fn betza(&self) -> &'static str {
+        return self.info().betza;
+    }
+
+    /// Material value of the role in centipawns. The king is worth more
+    /// than everything else together, losing it loses the game.
+    pub fn value(&self) -> i32 {
+        return self.info().value;
+    }
+
+    // Movement of the role, parsed once
+    pub(crate) fn movement(&self) -> &'static Movement {
+        let movements: &Vec<Movement> = MOVEMENTS.get_or_init(|| {
+            return ROLES.iter().map(|info| Movement::parse(info.betza).expect("roles have valid Betza notation")).collect();
+        });
+        return &movements[self.index()];
+    }
+}
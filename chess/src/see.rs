@@ -0,0 +1,95 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use crate::attacks::attackers;
+use crate::{Color, Dimensions, Game, Move, Piece, Position, Role};
+
+/* Static exchange evaluation.
+ *
+ * `Game::see` plays out every capture on the target square of a move,
+ * each side taking with its least valuable attacker, and tells how much
+ * material the side to move wins or loses in the end. Either side may stop
+ * capturing when going on would lose more. Pieces lined up behind a slider
+ * join in once the slider has captured, because the attackers are looked
+ * for again after every capture. Pawns recapturing on the last rank become
+ * queens, where the variant allows them.
+ *
+ * The exchange ends with the capture of a king, which ends the game in
+ * Chess royale. A king may capture into a defended square like any other
+ * piece, it is just worth more than everything else (`Role::value`).
+ * Variant rules such as explosions in Atomic are left out.
+ */
+
+impl Game {
+
+    /// Get the material in centipawns the active color wins with the captures on the target
+    /// square of a move, 0 for moves of pieces it does not have.
+    pub fn see(&self, _move: &Move) -> i32 {
+        let color: Color = self.active_color.clone();
+        let dimensions: Dimensions = self.get_dimensions();
+        let blocked: Vec<Position> = self.blocked_squares();
+
+        let mut board: HashMap<Position, Piece> = self.board.clone();
+        let mut piece: Piece = match board.remove(&_move.from) {
+            Some(piece) if piece.color == color => piece,
+            _ => return 0
+        };
+
+        // Material after each capture, seen from the side making it
+        let mut gains: Vec<i32> = Vec::new();
+        let captured: Option<Role> = board.get(&_move.to).map(|piece| piece.role.clone());
+        gains.push(captured.as_ref().map_or(0, |role| role.value()));
+
+        // A promotion brings its piece onto the square
+        if let Some(role) = &_move.promotion {
+            gains[0] += role.value() - piece.role.value();
+            piece.role = role.clone();
+        }
+
+        let mut standing: Role = piece.role.clone();
+        let mut king_taken: bool = captured == Some(Role::King);
+        piece.position = _move.to.clone();
+        board.insert(_move.to.clone(), piece);
+
+        // Pawns taking on the last rank promote
+        let last_rank: bool = _move.to.row == 1 || _move.to.row == dimensions.rows;
+        let queens: bool = last_rank && self.allows_promotion(&Role::Queen);
+
+        let mut side: Color = color.opponent();
+        while !king_taken {
+
+            // The least valuable attacker takes next
+            let next: Option<Position> = attackers(&board, &dimensions, &blocked, &_move.to, &side).into_iter()
+                .min_by_key(|square| board[square].role.value());
+            let from: Position = match next {
+                Some(from) => from,
+                None => break
+            };
+
+            let mut attacker: Piece = board.remove(&from).unwrap();
+            let mut gain: i32 = standing.value() - gains[gains.len() - 1];
+            if queens && attacker.role == Role::Pawn {
+                gain += Role::Queen.value() - Role::Pawn.value();
+                attacker.role = Role::Queen;
+            }
+
+            gains.push(gain);
+            king_taken = standing == Role::King;
+
+            standing = attacker.role.clone();
+            attacker.position = _move.to.clone();
+            board.insert(_move.to.clone(), attacker);
+
+            side = side.opponent();
+        }
+
+        // Each side stops capturing where going on loses
+        while gains.len() > 1 {
+            let last: i32 = gains.pop().unwrap();
+            let previous: usize = gains.len() - 1;
+            gains[previous] = -cmp::max(-gains[previous], last);
+        }
+
+        return gains[0];
+    }
+}
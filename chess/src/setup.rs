@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::castling::apply_castling_field;
+use crate::fen::placed_piece;
+use crate::{Color, Game, Piece, Position, Role, Variant};
+
+/* Setting up games from arbitrary positions, for odds games, endgame
+ * drills and puzzles.
+ *
+ *   let game: Game = SetupBuilder::new()
+ *       .place(Position::parse("e1").unwrap(), Color::White, Role::King)
+ *       .place(Position::parse("e8").unwrap(), Color::Black, Role::King)
+ *       .place(Position::parse("a7").unwrap(), Color::White, Role::Pawn)
+ *       .build()?;
+ *
+ * Nothing is checked until `build`, which refuses positions no game could
+ * continue from.
+ */
+
+/// Why a position could not be set up.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SetupError {
+    InvalidPosition,
+    MissingKing,
+    TooManyKings,
+    PawnOnBackRank,
+    InvalidCastling,
+    InvalidCounter,
+    AlreadyLost
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message: &str = match self {
+            SetupError::InvalidPosition => "Piece placed outside the board",
+            SetupError::MissingKing => "A side has no king",
+            SetupError::TooManyKings => "A side has more than one king",
+            SetupError::PawnOnBackRank => "Pawns cannot stand on the first or last rank",
+            SetupError::InvalidCastling => "Castling rights without king and rook in place",
+            SetupError::InvalidCounter => "Invalid move counter",
+            SetupError::AlreadyLost => "The side not to move has already lost"
+        };
+        write!(f, "{}", message)
+    }
+}
+
+/// Places pieces and sets the side to move and rights before starting a game.
+#[derive(Clone, Debug)]
+pub struct SetupBuilder {
+    pieces: HashMap<Position, (Color, Role)>,
+    active_color: Color,
+    castling: String,
+    variant: Variant,
+    chess960: bool,
+    checks: (u32, u32),
+    pockets: (Vec<Role>, Vec<Role>),
+    duck: Option<Position>,
+    halfmove_clock: u32,
+    fullmove_number: u32
+}
+
+impl SetupBuilder {
+
+    /// Start from an empty board with white to move and no castling rights.
+    pub fn new() -> SetupBuilder {
+        return SetupBuilder {
+            pieces: HashMap::new(),
+            active_color: Color::White,
+            castling: String::from("-"),
+            variant: Variant::Standard,
+            chess960: false,
+            checks: (0, 0),
+            pockets: (Vec::new(), Vec::new()),
+            duck: None,
+            halfmove_clock: 0,
+            fullmove_number: 1
+        };
+    }
+
+    /// Start from the current position of a game, e.g. `Game::new()` for odds games.
+    pub fn from_game(game: &Game) -> SetupBuilder {
+        return SetupBuilder {
+            pieces: game.board.values().map(|piece| (piece.position.clone(), (piece.color.clone(), piece.role.clone()))).collect(),
+            active_color: game.active_color.clone(),
+            castling: game.castling_field(false),
+            variant: game.variant,
+            chess960: game.chess960,
+            checks: (game.white_checks, game.black_checks),
+            pockets: (game.white_pocket.clone(), game.black_pocket.clone()),
+            duck: game.duck.clone(),
+            halfmove_clock: game.halfmove_clock,
+            fullmove_number: game.fullmove_number
+        };
+    }
+
+    /// Put a piece on a square, replacing whatever stood there.
+    pub fn place(mut self, position: Position, color: Color, role: Role) -> SetupBuilder {
+        self.pieces.insert(position, (color, role));
+        return self;
+    }
+
+    /// Take the piece off a square.
+    pub fn remove(mut self, position: &Position) -> SetupBuilder {
+        self.pieces.remove(position);
+        return self;
+    }
+
+    /// Set the color to move.
+    pub fn active_color(mut self, color: Color) -> SetupBuilder {
+        self.active_color = color;
+        return self;
+    }
+
+    /// Set the castling rights as in FEN, e.g. "KQkq", "HAha" or "-".
+    pub fn castling(mut self, rights: &str) -> SetupBuilder {
+        self.castling = rights.to_string();
+        return self;
+    }
+
+    /// Set the rules the game is played by.
+    pub fn variant(mut self, variant: Variant) -> SetupBuilder {
+        self.variant = variant;
+        return self;
+    }
+
+    /// Castle by the Chess960 rules. Rights naming rook files or a king off
+    /// the e-file turn this on by themselves.
+    pub fn chess960(mut self, chess960: bool) -> SetupBuilder {
+        self.chess960 = chess960;
+        return self;
+    }
+
+    /// Set the checks white and black have given, for Three-check.
+    pub fn checks(mut self, white: u32, black: u32) -> SetupBuilder {
+        self.checks = (white, black);
+        return self;
+    }
+
+    /// Set the pieces a color may drop, for Crazyhouse.
+    pub fn pocket(mut self, color: Color, roles: Vec<Role>) -> SetupBuilder {
+        match color {
+            Color::White => self.pockets.0 = roles,
+            Color::Black => self.pockets.1 = roles
+        }
+        return self;
+    }
+
+    /// Put the duck on a square, for Duck chess.
+    pub fn duck(mut self, position: Position) -> SetupBuilder {
+        self.duck = Some(position);
+        return self;
+    }
+
+    /// Set the halfmove clock and the move number.
+    pub fn counters(mut self, halfmove_clock: u32, fullmove_number: u32) -> SetupBuilder {
+        self.halfmove_clock = halfmove_clock;
+        self.fullmove_number = fullmove_number;
+        return self;
+    }
+
+    /// Check the position and start a game from it.
+    pub fn build(&self) -> Result<Game, SetupError> {
+        let mut board: HashMap<Position, Piece> = HashMap::new();
+
+        for (position, (color, role)) in self.pieces.iter() {
+            if !self.variant.dimensions().contains(position) {
+                return Err(SetupError::InvalidPosition);
+            }
+            board.insert(position.clone(), placed_piece(color.clone(), role.clone(), position.clone()));
+        }
+
+        // The duck needs a square of its own
+        if let Some(duck) = &self.duck {
+            if !self.variant.dimensions().contains(duck) || board.contains_key(duck) {
+                return Err(SetupError::InvalidPosition);
+            }
+        }
+
+        if self.fullmove_number == 0 {
+            return Err(SetupError::InvalidCounter);
+        }
+
+        let chess960: bool = match apply_castling_field(&mut board, &self.variant.dimensions(), &self.castling) {
+            Ok(chess960) => chess960 || self.chess960,
+            Err(_) => return Err(SetupError::InvalidCastling)
+        };
+
+        let mut game: Game = Game::with_board(board, self.active_color.clone(), self.halfmove_clock, self.fullmove_number, self.variant, chess960);
+        game.white_checks = self.checks.0;
+        game.black_checks = self.checks.1;
+        game.white_pocket = self.pockets.0.clone();
+        game.black_pocket = self.pockets.1.clone();
+        game.duck = self.duck.clone();
+        game.start_fen = game.to_fen();
+
+        // Pawns never stand on the first or last rank, unless the variant puts them there
+        if game.board.values().any(|piece| piece.role == Role::Pawn && !game.allows_pawn_on(&piece.color, piece.position.row)) {
+            return Err(SetupError::PawnOnBackRank);
+        }
+
+        // Exactly one king per side, unless the variant does without
+        for color in [Color::White, Color::Black].iter() {
+            let kings: usize = game.board.values().filter(|piece| piece.role == Role::King && piece.color == *color).count();
+            if kings == 0 && game.requires_king(color) {
+                return Err(SetupError::MissingKing);
+            }
+            if kings > 1 && game.requires_king(color) {
+                return Err(SetupError::TooManyKings);
+            }
+        }
+
+        // The side that just moved cannot already be beaten
+        if game.has_lost(&self.active_color.opponent()) {
+            return Err(SetupError::AlreadyLost);
+        }
+
+        return Ok(game);
+    }
+}
+
+impl Default for SetupBuilder {
+    fn default() -> Self {
+        return SetupBuilder::new();
+    }
+}
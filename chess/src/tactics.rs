@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use crate::attacks::attackers;
+use crate::{Color, Dimensions, Game, Piece, Position, Role};
+
+/* Tactical motifs.
+ *
+ * `Game::tactics` looks at the position from one side and lists what it
+ * can use against the other one. Everything is read off the attack maps
+ * of the pieces as they stand, so the motifs are there whichever move is
+ * played next:
+ *
+ * - A pin holds a piece in front of a more valuable one, a skewer a more
+ *   valuable piece in front of a less valuable one. Both are found by
+ *   taking the front piece away and looking what the attacker sees behind
+ *   it. Pins in front of the king are absolute, moving the piece loses
+ *   the game.
+ * - A fork attacks two or more pieces which are worth more than the
+ *   forking piece or are not defended.
+ * - A discovered attack needs a piece which can step out of the line
+ *   between an own piece and an enemy piece.
+ * - A hanging piece is attacked and not defended, an overloaded defender
+ *   is the only one defending two or more attacked pieces.
+ *
+ * Values come from `Role::value`.
+ */
+
+/// A tactical motif and the squares involved.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tactic {
+    /// A piece cannot move away without exposing a more valuable one behind it.
+    /// The pin is absolute when the king stands behind.
+    Pin { pinner: Position, pinned: Position, behind: Position, absolute: bool },
+
+    /// A piece attacks several pieces at once, each worth more than it or undefended.
+    Fork { forker: Position, targets: Vec<Position> },
+
+    /// A piece is attacked and has a less valuable one behind it.
+    Skewer { attacker: Position, front: Position, behind: Position },
+
+    /// Moving a piece out of the way lets a piece behind it attack.
+    DiscoveredAttack { mover: Position, attacker: Position, target: Position },
+
+    /// An attacked piece nothing defends.
+    Hanging { piece: Position, attackers: Vec<Position> },
+
+    /// A piece is the only defender of several attacked pieces.
+    Overloaded { defender: Position, defended: Vec<Position> }
+}
+
+impl Game {
+
+    /// Find the motifs the given color can use against the other one,
+    /// pins and skewers first, then forks, discovered attacks, hanging
+    /// pieces and overloaded defenders, each ordered by square.
+    pub fn tactics(&self, _color: Color) -> Vec<Tactic> {
+        let mut tactics: Vec<Tactic> = Vec::new();
+
+        tactics.append(&mut self.pins_and_skewers(&_color));
+        tactics.append(&mut self.forks(&_color));
+        tactics.append(&mut self.discovered_attacks(&_color));
+        tactics.append(&mut self.hanging_pieces(&_color));
+        tactics.append(&mut self.overloaded_defenders(&_color));
+
+        return tactics;
+    }
+
+    fn pins_and_skewers(&self, _color: &Color) -> Vec<Tactic> {
+        let mut tactics: Vec<Tactic> = Vec::new();
+        let dimensions: Dimensions = self.get_dimensions();
+        let blocked: Vec<Position> = self.blocked_squares();
+
+        for attacker in self.pieces_of(_color) {
+            let before: Vec<Position> = targets(attacker, &self.board, &dimensions, &blocked);
+
+            for front in before.iter() {
+
+                // Look through the front piece
+                let mut board: HashMap<Position, Piece> = self.board.clone();
+                let front_piece: Piece = board.remove(front).unwrap();
+
+                for behind in targets(attacker, &board, &dimensions, &blocked) {
+                    if before.contains(&behind) || !lies_behind(&attacker.position, front, &behind) {
+                        continue;
+                    }
+
+                    let behind_piece: &Piece = &board[&behind];
+                    if behind_piece.role.value() > front_piece.role.value() {
+                        tactics.push(Tactic::Pin {
+                            pinner: attacker.position.clone(),
+                            pinned: front.clone(),
+                            behind: behind.clone(),
+                            absolute: behind_piece.role == Role::King
+                        });
+                    } else if behind_piece.role.value() < front_piece.role.value() {
+                        tactics.push(Tactic::Skewer { attacker: attacker.position.clone(), front: front.clone(), behind });
+                    }
+                }
+            }
+        }
+
+        return tactics;
+    }
+
+    fn forks(&self, _color: &Color) -> Vec<Tactic> {
+        let mut tactics: Vec<Tactic> = Vec::new();
+        let dimensions: Dimensions = self.get_dimensions();
+        let blocked: Vec<Position> = self.blocked_squares();
+
+        for forker in self.pieces_of(_color) {
+
+            // Only pieces worth taking count
+            let forked: Vec<Position> = targets(forker, &self.board, &dimensions, &blocked).into_iter()
+                .filter(|target| self.board[target].role.value() > forker.role.value() || !self.is_defended(target))
+                .collect();
+
+            if forked.len() >= 2 {
+                tactics.push(Tactic::Fork { forker: forker.position.clone(), targets: forked });
+            }
+        }
+
+        return tactics;
+    }
+
+    fn discovered_attacks(&self, _color: &Color) -> Vec<Tactic> {
+        let mut tactics: Vec<Tactic> = Vec::new();
+        let dimensions: Dimensions = self.get_dimensions();
+        let blocked: Vec<Position> = self.blocked_squares();
+
+        for attacker in self.pieces_of(_color) {
+            let before: Vec<Position> = targets(attacker, &self.board, &dimensions, &blocked);
+
+            for mover in self.pieces_of(_color) {
+                if mover.position == attacker.position {
+                    continue;
+                }
+
+                let mut board: HashMap<Position, Piece> = self.board.clone();
+                board.remove(&mover.position);
+
+                for target in targets(attacker, &board, &dimensions, &blocked) {
+                    if before.contains(&target) || !lies_behind(&attacker.position, &mover.position, &target) {
+                        continue;
+                    }
+
+                    // The mover needs a square off the line, castling onto own rooks does not count
+                    let discovers = |to: &Position| -> bool {
+                        match board.get(to) {
+                            Some(other) if other.color == mover.color => return false,
+                            _ => ()
+                        }
+                        let mut after: HashMap<Position, Piece> = board.clone();
+                        let mut moved: Piece = mover.clone();
+                        moved.position = to.clone();
+                        after.insert(to.clone(), moved);
+                        return targets(attacker, &after, &dimensions, &blocked).contains(&target);
+                    };
+
+                    if self.piece_moves(mover).iter().any(discovers) {
+                        tactics.push(Tactic::DiscoveredAttack {
+                            mover: mover.position.clone(),
+                            attacker: attacker.position.clone(),
+                            target
+                        });
+                    }
+                }
+            }
+        }
+
+        return tactics;
+    }
+
+    fn hanging_pieces(&self, _color: &Color) -> Vec<Tactic> {
+        let mut tactics: Vec<Tactic> = Vec::new();
+
+        for piece in self.pieces_of(&_color.opponent()) {
+            let attackers: Vec<Position> = self.attackers(&piece.position, _color.clone());
+            if !attackers.is_empty() && !self.is_defended(&piece.position) {
+                tactics.push(Tactic::Hanging { piece: piece.position.clone(), attackers });
+            }
+        }
+
+        return tactics;
+    }
+
+    fn overloaded_defenders(&self, _color: &Color) -> Vec<Tactic> {
+        let mut tactics: Vec<Tactic> = Vec::new();
+        let opponent: Color = _color.opponent();
+
+        for defender in self.pieces_of(&opponent) {
+            let defended: Vec<Position> = self.pieces_of(&opponent).into_iter()
+                .filter(|piece| self.is_attacked(&piece.position, _color.clone()))
+                .filter(|piece| self.attackers(&piece.position, opponent.clone()) == vec![defender.position.clone()])
+                .map(|piece| piece.position.clone())
+                .collect();
+
+            if defended.len() >= 2 {
+                tactics.push(Tactic::Overloaded { defender: defender.position.clone(), defended });
+            }
+        }
+
+        return tactics;
+    }
+
+    // Whether a piece of the same color attacks the piece on a square
+    fn is_defended(&self, _square: &Position) -> bool {
+        let color: Color = self.board[_square].color.clone();
+        return !attackers(&self.board, &self.get_dimensions(), &self.blocked_squares(), _square, &color).is_empty();
+    }
+
+    // Pieces of a color, ordered by square
+    fn pieces_of(&self, _color: &Color) -> Vec<&Piece> {
+        let mut pieces: Vec<&Piece> = self.board.values().filter(|piece| piece.color == *_color).collect();
+        pieces.sort_by(|a, b| a.position.cmp(&b.position));
+        return pieces;
+    }
+}
+
+// Squares of the pieces of the other color a piece attacks on a board, ordered by square
+fn targets(piece: &Piece, board: &HashMap<Position, Piece>, dimensions: &Dimensions, blocked: &[Position]) -> Vec<Position> {
+    let mut targets: Vec<Position> = match piece.get_attacks(board, dimensions, blocked) {
+        Some(attacks) => attacks.into_iter().filter(|square| match board.get(square) {
+            Some(other) => other.color != piece.color,
+            None => false
+        }).collect(),
+        None => Vec::new()
+    };
+
+    targets.sort();
+    return targets;
+}
+
+// Whether a square lies further along the line from an origin through another square
+fn lies_behind(origin: &Position, front: &Position, square: &Position) -> bool {
+    let (front_column, front_row) = ((front.column - origin.column) as i32, (front.row - origin.row) as i32);
+    let (column, row) = ((square.column - origin.column) as i32, (square.row - origin.row) as i32);
+
+    return front_column * row == front_row * column
+        && front_column * column >= 0
+        && front_row * row >= 0
+        && column.abs() + row.abs() > front_column.abs() + front_row.abs();
+}
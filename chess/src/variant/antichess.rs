@@ -0,0 +1,53 @@
+use crate::{Color, Game, Piece, Position};
+
+/* Antichess, also known as giveaway.
+ *
+ * - Capturing is compulsory. While any piece of a side can capture, only
+ *   captures may be played, but the player picks which one.
+ * - The king is an ordinary piece. It may be captured, and pawns may be
+ *   promoted to it.
+ * - There is no castling.
+ * - A side wins when it has lost all its pieces or cannot move.
+ */
+
+impl Game {
+
+    // Only captures while the side can capture anything
+    pub(crate) fn antichess_moves(&self, piece: &Piece, moves: Vec<Position>) -> Vec<Position> {
+        if !self.can_capture(&piece.color) {
+            return moves;
+        }
+
+        return moves.into_iter().filter(|target| self.is_capture(piece, target)).collect();
+    }
+
+    // The opponent of the given color won by running out of pieces or moves
+    pub(crate) fn antichess_lost(&self, _color: &Color) -> bool {
+        let opponent: Color = _color.opponent();
+        let pieces: Vec<&Piece> = self.board.values().filter(|piece| piece.color == opponent).collect();
+
+        if pieces.is_empty() {
+            return true;
+        }
+
+        // Running out of moves only counts for the side to move
+        return opponent == self.active_color && pieces.iter().all(|piece| self.piece_moves(piece).is_empty());
+    }
+
+    // Whether any piece of the color can take an opposing piece
+    fn can_capture(&self, _color: &Color) -> bool {
+        return self.board.values()
+            .filter(|piece| piece.color == *_color)
+            .any(|piece| match piece.get_possible_moves(&self.board, &self.get_dimensions()) {
+                Some(moves) => moves.iter().any(|target| self.is_capture(piece, target)),
+                None => false
+            });
+    }
+
+    fn is_capture(&self, piece: &Piece, target: &Position) -> bool {
+        match self.board.get(target) {
+            Some(other) => return other.color != piece.color,
+            None => return false
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{Game, Piece, Position, Role};
+
+/* Atomic chess.
+ *
+ * Every capture is an explosion: the capturing piece, the captured piece
+ * and all pieces but pawns on the eight surrounding squares leave the
+ * board. A side whose king explodes has lost.
+ *
+ * Kings never capture, as they would blow themselves up, and no capture
+ * may explode the own king. Kings standing next to each other are
+ * therefore safe from explosions.
+ */
+
+impl Game {
+
+    // Drop captures which would blow up the own king
+    pub(crate) fn atomic_moves(&self, piece: &Piece, moves: Vec<Position>) -> Vec<Position> {
+        return moves.into_iter().filter(|target| {
+            let capture: bool = match self.board.get(target) {
+                Some(other) => other.color != piece.color,
+                None => false
+            };
+            if !capture {
+                return true;
+            }
+            if piece.role == Role::King {
+                return false;
+            }
+
+            return !self.board.values().any(|other| other.role == Role::King && other.color == piece.color && adjacent(&other.position, target));
+        }).collect();
+    }
+
+    // Remove the piece on the square and everything but pawns around it
+    pub(crate) fn explode(&mut self, position: &Position) {
+        self.take_piece(position);
+        let blown: Vec<Position> = self.board.values()
+            .filter(|piece| piece.role != Role::Pawn && adjacent(&piece.position, position))
+            .map(|piece| piece.position.clone())
+            .collect();
+        for square in blown.iter() {
+            self.take_piece(square);
+        }
+    }
+}
+
+// Whether two different squares touch, diagonals included
+fn adjacent(a: &Position, b: &Position) -> bool {
+    return a != b && (a.row - b.row).abs() <= 1 && (a.column - b.column).abs() <= 1;
+}
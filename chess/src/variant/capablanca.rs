@@ -0,0 +1,9 @@
+/* Capablanca chess.
+ *
+ * A 10x8 board with an archbishop (bishop and knight) and a chancellor
+ * (rook and knight) next to the bishops. The king starts on the f-file and
+ * castles three squares, to i1 with the rook on h1 or to c1 with the rook
+ * on d1. Pawns may promote to the two new pieces as well.
+ */
+
+pub(crate) const CAPABLANCA_FEN: &str = "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1";
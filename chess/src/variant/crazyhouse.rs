@@ -0,0 +1,156 @@
+use crate::fen::placed_piece;
+use crate::{Color, Dimensions, Game, GameState, MoveError, Piece, Position, Role};
+
+/* Crazyhouse.
+ *
+ * - A captured piece changes sides and goes into the pocket of the capturer.
+ * - Instead of moving, a player may drop a piece from the pocket onto any
+ *   empty square. Pawns cannot be dropped on the first or last rank.
+ * - Promoted pieces turn back into pawns when they are captured.
+ *
+ * Drops are written as the role, "@" and the square, e.g. "N@f3". FEN
+ * lists the pockets in brackets after the placement, "[Qn]", and marks
+ * promoted pieces with "~".
+ *
+ * Each board of Bughouse follows the same drop rules, but captured pieces
+ * go to the partner on the other board, see `Bughouse`.
+ */
+
+// Order of roles in pocket notation
+const POCKET_ORDER: [Role; 5] = [Role::Queen, Role::Rook, Role::Bishop, Role::Knight, Role::Pawn];
+
+impl Game {
+
+    /// Get the pieces a color holds in its pocket, in Crazyhouse and Bughouse.
+    pub fn get_pocket(&self, _color: &Color) -> Vec<Role> {
+        match _color {
+            Color::White => return self.white_pocket.clone(),
+            Color::Black => return self.black_pocket.clone()
+        }
+    }
+
+    /// If the game is Crazyhouse or Bughouse and in progress, drop a piece from the pocket
+    /// of the active color on an empty square and return the resulting state.
+    pub fn drop_piece(&mut self, _role: Role, _to: String) -> Option<GameState> {
+        match self.try_drop(_role, &_to) {
+            Ok(state) => return Some(state),
+            Err(error) => {
+                println!("{}", error);
+                return Some(self.state);
+            }
+        }
+    }
+
+    /// Same as `drop_piece`, but tells why a drop was rejected instead of printing it.
+    pub fn try_drop(&mut self, _role: Role, _to: &str) -> Result<GameState, MoveError> {
+
+        // Game is either over or waiting for promotion or the duck
+        match self.state {
+            GameState::GameOver => return Err(MoveError::GameOver),
+            GameState::Promotion => return Err(MoveError::PromotionPending),
+            GameState::DuckPlacement => return Err(MoveError::DuckPending),
+            _ => ()
+        }
+
+        let to_pos: Position = self.parse_square(_to)?;
+        if !self.get_legal_drops().contains(&(_role.clone(), to_pos.clone())) {
+            return Err(MoveError::IllegalMove);
+        }
+
+        // Take the piece out of the pocket
+        let color: Color = self.active_color.clone();
+        let pocket: &mut Vec<Role> = self.pocket_mut(&color);
+        let index: usize = pocket.iter().position(|role| *role == _role).unwrap();
+        pocket.remove(index);
+
+        // Pawn drops reset the clock like pawn moves
+        if _role == Role::Pawn {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.record {
+            self.history.push(format!("{}@{}", _role.to_char(), to_pos));
+        }
+
+        // Dropped rooks never castle, pawns dropped on their start rank may still double step
+        let mut piece: Piece = placed_piece(color, _role, to_pos.clone());
+        piece.has_moved = piece.has_moved || piece.role != Role::Pawn;
+        self.put_piece(piece);
+
+        return Ok(self.finish_move());
+    }
+
+    /// Get every drop the active color can make, ordered by square.
+    pub fn get_legal_drops(&self) -> Vec<(Role, Position)> {
+        let mut drops: Vec<(Role, Position)> = Vec::new();
+
+        if !self.has_pockets() || self.state == GameState::GameOver || self.state == GameState::Promotion || self.state == GameState::DuckPlacement {
+            return drops;
+        }
+
+        let pocket: Vec<Role> = self.get_pocket(&self.active_color);
+        let dimensions: Dimensions = self.get_dimensions();
+        for row in 1..=dimensions.rows {
+            for column in 1..=dimensions.columns {
+                let position: Position = Position { row, column };
+                if self.board.contains_key(&position) {
+                    continue;
+                }
+
+                for role in POCKET_ORDER.iter().filter(|role| pocket.contains(role)) {
+                    if *role == Role::Pawn && (row == 1 || row == dimensions.rows) {
+                        continue;
+                    }
+                    drops.push((role.clone(), position.clone()));
+                }
+            }
+        }
+
+        return drops;
+    }
+
+    // Put a captured piece into the pocket of the capturer
+    pub(crate) fn pocket_capture(&mut self, captured: Piece) {
+        let color: Color = captured.color.opponent();
+        self.pocket_piece(&color, captured);
+    }
+
+    // Put a captured piece into a pocket, a captured king ends the game
+    // and there is nothing to drop
+    pub(crate) fn pocket_piece(&mut self, _color: &Color, captured: Piece) {
+        if captured.role == Role::King {
+            return;
+        }
+
+        let role: Role = if captured.promoted { Role::Pawn } else { captured.role };
+        self.pocket_mut(_color).push(role);
+    }
+
+    // Pockets in FEN, white pieces first
+    pub(crate) fn pocket_field(&self) -> String {
+        let mut field: String = String::new();
+
+        for color in [Color::White, Color::Black].iter() {
+            let pocket: Vec<Role> = self.get_pocket(color);
+
+            for role in POCKET_ORDER.iter() {
+                for _ in pocket.iter().filter(|other| *other == role) {
+                    match color {
+                        Color::White => field.push(role.to_char()),
+                        Color::Black => field.push(role.to_char().to_ascii_lowercase())
+                    }
+                }
+            }
+        }
+
+        return format!("[{}]", field);
+    }
+
+    pub(crate) fn pocket_mut(&mut self, _color: &Color) -> &mut Vec<Role> {
+        match _color {
+            Color::White => return &mut self.white_pocket,
+            Color::Black => return &mut self.black_pocket
+        }
+    }
+}
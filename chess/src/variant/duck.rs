@@ -0,0 +1,82 @@
+use crate::{Dimensions, Game, GameState, MoveError, Position};
+
+/* Duck chess.
+ *
+ * - After every move the player puts the duck on another empty square, and
+ *   only then it is the opponent's turn. The duck enters the board after
+ *   the first move.
+ * - No piece can capture the duck or pass it, it blocks like a piece of
+ *   the own color for everyone.
+ * - There is no check, capturing the king wins and ends the game without
+ *   placing the duck.
+ *
+ * The duck square is written after the move, e.g. "e4@d5", and FEN marks
+ * the duck with "*", e.g. "8/8/3*4/...".
+ */
+
+impl Game {
+
+    /// Get the square of the duck, in Duck chess once the first move is made.
+    pub fn get_duck(&self) -> Option<Position> {
+        return self.duck.clone();
+    }
+
+    /// If the game is waiting for the duck, put it on an empty square and
+    /// return the resulting state. The opponent moves next.
+    pub fn place_duck(&mut self, _to: String) -> Option<GameState> {
+        match self.try_place_duck(&_to) {
+            Ok(state) => return Some(state),
+            Err(error) => {
+                println!("{}", error);
+                return Some(self.state);
+            }
+        }
+    }
+
+    /// Same as `place_duck`, but tells why the duck was not placed instead of printing it.
+    pub fn try_place_duck(&mut self, _to: &str) -> Result<GameState, MoveError> {
+        match self.state {
+            GameState::GameOver => return Err(MoveError::GameOver),
+            GameState::Promotion => return Err(MoveError::PromotionPending),
+            GameState::DuckPlacement => (),
+            _ => return Err(MoveError::IllegalMove)
+        }
+
+        let to_pos: Position = self.parse_square(_to)?;
+        if !self.get_duck_squares().contains(&to_pos) {
+            return Err(MoveError::IllegalMove);
+        }
+
+        // The duck completes the notation of the move
+        if let Some(notation) = self.history.last_mut() {
+            notation.push('@');
+            notation.push_str(&to_pos.to_string());
+        }
+        self.duck = Some(to_pos);
+
+        self.state = GameState::InProgress;
+        return Ok(self.pass_turn());
+    }
+
+    /// Get every square the duck can be put on, ordered by square. The
+    /// duck has to move, so its own square is left out.
+    pub fn get_duck_squares(&self) -> Vec<Position> {
+        let mut squares: Vec<Position> = Vec::new();
+
+        if self.state != GameState::DuckPlacement {
+            return squares;
+        }
+
+        let dimensions: Dimensions = self.get_dimensions();
+        for row in 1..=dimensions.rows {
+            for column in 1..=dimensions.columns {
+                let position: Position = Position { row, column };
+                if !self.board.contains_key(&position) && self.duck.as_ref() != Some(&position) {
+                    squares.push(position);
+                }
+            }
+        }
+
+        return squares;
+    }
+}
@@ -0,0 +1,7 @@
+/* Gardner minichess.
+ *
+ * A 5x5 board with one of each piece and five pawns. Pawns never step
+ * twice and there is no castling.
+ */
+
+pub(crate) const GARDNER_FEN: &str = "rnbqk/ppppp/5/PPPPP/RNBQK w - - 0 1";
@@ -0,0 +1,12 @@
+/* Grand chess.
+ *
+ * A 10x10 board with the rooks in the corners, the other pieces on the
+ * second rank and the pawns on the third, from where they may step twice.
+ * The archbishop (bishop and knight) and the chancellor (rook and knight)
+ * join the usual pieces. There is no castling.
+ *
+ * Pawns promote on the last rank only. The original rules also allow
+ * promotion on the two ranks before it, but only to pieces already lost.
+ */
+
+pub(crate) const GRAND_FEN: &str = "r8r/1nbqkcabn1/pppppppppp/10/10/10/10/PPPPPPPPPP/1NBQKCABN1/R8R w - - 0 1";
@@ -0,0 +1,21 @@
+use crate::{Color, Game, Role};
+
+/* Horde.
+ *
+ * White has 36 pawns and no king, black the usual army. White wins by
+ * capturing the black king, black by capturing every white piece. Pawns
+ * on the first rank may move two squares, like pawns on the second.
+ */
+
+pub(crate) const HORDE_FEN: &str = "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1";
+
+impl Game {
+
+    // White has lost when nothing of the horde is left, black when the king is gone
+    pub(crate) fn horde_lost(&self, _color: &Color) -> bool {
+        match _color {
+            Color::White => return !self.board.values().any(|piece| piece.color == Color::White),
+            Color::Black => return !self.board.values().any(|piece| piece.color == Color::Black && piece.role == Role::King)
+        }
+    }
+}
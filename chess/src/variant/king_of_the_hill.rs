@@ -0,0 +1,19 @@
+use crate::{Color, Game, Position, Role};
+
+/* King of the Hill.
+ *
+ * Besides capturing the king, a side wins by bringing its own king to one
+ * of the four center squares d4, d5, e4 and e5.
+ */
+
+impl Game {
+
+    // Whether the king of the color stands in the center
+    pub(crate) fn king_on_hill(&self, _color: &Color) -> bool {
+        return self.board.values().any(|piece| piece.role == Role::King && piece.color == *_color && on_hill(&piece.position));
+    }
+}
+
+fn on_hill(position: &Position) -> bool {
+    return (position.row == 4 || position.row == 5) && (position.column == 4 || position.column == 5);
+}
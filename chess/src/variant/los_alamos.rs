@@ -0,0 +1,7 @@
+/* Los Alamos chess, the first chess game played by a computer.
+ *
+ * A 6x6 board without bishops. Pawns never step twice, there is no
+ * castling and pawns cannot promote to bishops.
+ */
+
+pub(crate) const LOS_ALAMOS_FEN: &str = "rnqknr/pppppp/6/6/PPPPPP/RNQKNR w - - 0 1";
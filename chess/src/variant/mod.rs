@@ -0,0 +1,218 @@
+use std::fmt;
+
+use crate::{Color, Dimensions, Game, Piece, Position, Role};
+
+use capablanca::CAPABLANCA_FEN;
+use gardner::GARDNER_FEN;
+use grand::GRAND_FEN;
+use horde::HORDE_FEN;
+use los_alamos::LOS_ALAMOS_FEN;
+use racing_kings::RACING_KINGS_FEN;
+
+mod antichess;
+mod atomic;
+mod capablanca;
+mod crazyhouse;
+mod duck;
+mod gardner;
+mod grand;
+mod horde;
+mod king_of_the_hill;
+mod los_alamos;
+mod racing_kings;
+mod three_check;
+
+pub(crate) use three_check::CHECKS_TO_WIN;
+
+/* Rule variants. Each variant lives in its own module and hooks into the
+ * game at a few places:
+ *
+ * - `filter_moves` removes moves a piece could make in Chess royale but not
+ *   in the variant.
+ * - `after_capture` changes the board once a capturing move is made, and
+ *   gets the captured piece.
+ * - `has_lost` tells when a side has lost, and decides when the game ends.
+ *
+ * Variants on other boards than 8x8 give their size in `dimensions`, and
+ * Duck chess waits for the duck in `finish_move` before the turn passes.
+ */
+
+/// Rules a game is played by.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    Standard,
+    Atomic,
+    Antichess,
+    KingOfTheHill,
+    ThreeCheck,
+    Crazyhouse,
+    Horde,
+    RacingKings,
+    LosAlamos,
+    Gardner,
+    Capablanca,
+    Grand,
+    Bughouse,
+    Duck
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name: &str = match self {
+            Variant::Standard => "Standard",
+            Variant::Atomic => "Atomic",
+            Variant::Antichess => "Antichess",
+            Variant::KingOfTheHill => "King of the Hill",
+            Variant::ThreeCheck => "Three-check",
+            Variant::Crazyhouse => "Crazyhouse",
+            Variant::Horde => "Horde",
+            Variant::RacingKings => "Racing Kings",
+            Variant::LosAlamos => "Los Alamos",
+            Variant::Gardner => "Gardner",
+            Variant::Capablanca => "Capablanca",
+            Variant::Grand => "Grand",
+            Variant::Bughouse => "Bughouse",
+            Variant::Duck => "Duck"
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Variant {
+
+    /// Get the size of the board the variant is played on.
+    pub fn dimensions(&self) -> Dimensions {
+        match self {
+            Variant::LosAlamos => return Dimensions { columns: 6, rows: 6 },
+            Variant::Gardner => return Dimensions { columns: 5, rows: 5 },
+            Variant::Capablanca => return Dimensions { columns: 10, rows: 8 },
+            Variant::Grand => return Dimensions { columns: 10, rows: 10 },
+            _ => return Dimensions::STANDARD
+        }
+    }
+}
+
+impl Game {
+
+    /// Initialises a new board with pieces for the given variant.
+    pub fn new_variant(variant: Variant) -> Game {
+        match variant {
+            Variant::Horde => return Game::from_fen_variant(HORDE_FEN, variant).unwrap(),
+            Variant::RacingKings => return Game::from_fen_variant(RACING_KINGS_FEN, variant).unwrap(),
+            Variant::LosAlamos => return Game::from_fen_variant(LOS_ALAMOS_FEN, variant).unwrap(),
+            Variant::Gardner => return Game::from_fen_variant(GARDNER_FEN, variant).unwrap(),
+            Variant::Capablanca => return Game::from_fen_variant(CAPABLANCA_FEN, variant).unwrap(),
+            Variant::Grand => return Game::from_fen_variant(GRAND_FEN, variant).unwrap(),
+            _ => {
+                let mut game: Game = Game::new();
+                game.variant = variant;
+                return game;
+            }
+        }
+    }
+
+    /// Get the variant the game is played by.
+    pub fn get_variant(&self) -> Variant {
+        return self.variant;
+    }
+
+    /// Get the size of the board.
+    pub fn get_dimensions(&self) -> Dimensions {
+        return self.variant.dimensions();
+    }
+
+    // Remove the moves the variant does not allow
+    pub(crate) fn filter_moves(&self, piece: &Piece, moves: Vec<Position>) -> Vec<Position> {
+        match self.variant {
+            Variant::Atomic => return self.atomic_moves(piece, moves),
+            Variant::Antichess => return self.antichess_moves(piece, moves),
+            Variant::RacingKings => return self.racing_kings_moves(piece, moves),
+            _ => return moves
+        }
+    }
+
+    // Resolve what a capture on the given square sets off
+    pub(crate) fn after_capture(&mut self, position: &Position, captured: Piece) {
+        match self.variant {
+            Variant::Atomic => self.explode(position),
+            Variant::Crazyhouse => self.pocket_capture(captured),
+            _ => ()
+        }
+    }
+
+    // Check if the given color has lost
+    pub(crate) fn has_lost(&self, _color: &Color) -> bool {
+        let king_lost: bool = !self.board.values().any(|piece| piece.role == Role::King && piece.color == *_color);
+
+        match self.variant {
+
+            // Losing the king
+            Variant::Standard | Variant::Atomic | Variant::Crazyhouse | Variant::Bughouse => return king_lost,
+            Variant::LosAlamos | Variant::Gardner | Variant::Capablanca | Variant::Grand => return king_lost,
+            Variant::Duck => return king_lost,
+
+            // The opponent got rid of all pieces or cannot move
+            Variant::Antichess => return self.antichess_lost(_color),
+
+            // Or the opponent reached the hill or gave the third check
+            Variant::KingOfTheHill => return king_lost || self.king_on_hill(&_color.opponent()),
+            Variant::ThreeCheck => return king_lost || self.checks_exhausted(_color),
+
+            // White loses the horde, black the king
+            Variant::Horde => return self.horde_lost(_color),
+
+            // The opponent's king reached the last rank
+            Variant::RacingKings => return king_lost || self.king_finished(&_color.opponent())
+        }
+    }
+
+    // Whether sides hold pockets of pieces to drop
+    pub(crate) fn has_pockets(&self) -> bool {
+        return self.variant == Variant::Crazyhouse || self.variant == Variant::Bughouse;
+    }
+
+    // Whether checks are counted and shown in the game state
+    pub(crate) fn reports_check(&self) -> bool {
+        return self.variant == Variant::KingOfTheHill || self.variant == Variant::ThreeCheck;
+    }
+
+    // Whether a side must have exactly one king
+    pub(crate) fn requires_king(&self, _color: &Color) -> bool {
+        match self.variant {
+            Variant::Antichess => return false,
+            Variant::Horde => return *_color == Color::Black,
+            _ => return true
+        }
+    }
+
+    // Whether a pawn of the color may stand on the given row
+    pub(crate) fn allows_pawn_on(&self, _color: &Color, row: i8) -> bool {
+
+        // The horde starts with pawns on the first rank
+        if self.variant == Variant::Horde && *_color == Color::White && row == 1 {
+            return true;
+        }
+        return row != 1 && row != self.get_dimensions().rows;
+    }
+
+    // Whether kings and rooks may castle
+    pub(crate) fn allows_castling(&self) -> bool {
+        match self.variant {
+            Variant::Antichess | Variant::RacingKings => return false,
+            Variant::LosAlamos | Variant::Gardner | Variant::Grand => return false,
+            _ => return true
+        }
+    }
+
+    // Whether a pawn may become the given role
+    pub(crate) fn allows_promotion(&self, role: &Role) -> bool {
+        match role {
+            Role::Pawn => return false,
+            Role::King => return self.variant == Variant::Antichess,
+            Role::Bishop => return self.variant != Variant::LosAlamos,
+            Role::Archbishop | Role::Chancellor => return self.variant == Variant::Capablanca || self.variant == Variant::Grand,
+            Role::Amazon | Role::Nightrider | Role::Grasshopper => return false,
+            _ => return true
+        }
+    }
+}
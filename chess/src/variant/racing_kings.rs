@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use crate::{king_attacked, Color, Dimensions, Game, Piece, Position, Role};
+
+/* Racing Kings.
+ *
+ * Both kings start on the first rank and race to the eighth, the first
+ * king to arrive wins. No move may give check or leave the own king in
+ * check, so kings are never captured. There are no pawns and no castling.
+ *
+ * White moves first and wins on arrival, black gets no move to draw.
+ */
+
+pub(crate) const RACING_KINGS_FEN: &str = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+
+impl Game {
+
+    // Drop moves which would put either king in check
+    pub(crate) fn racing_kings_moves(&self, piece: &Piece, moves: Vec<Position>) -> Vec<Position> {
+        return moves.into_iter().filter(|target| {
+
+            // Play the move on a copy of the board
+            let mut board: HashMap<Position, Piece> = self.board.clone();
+            let mut moved: Piece = board.remove(&piece.position).unwrap();
+            moved.position = target.clone();
+            board.insert(target.clone(), moved);
+
+            return !king_attacked(&board, &Dimensions::STANDARD, &Color::White) && !king_attacked(&board, &Dimensions::STANDARD, &Color::Black);
+        }).collect();
+    }
+
+    // Whether the king of the color reached the last rank
+    pub(crate) fn king_finished(&self, _color: &Color) -> bool {
+        return self.board.values().any(|piece| piece.role == Role::King && piece.color == *_color && piece.position.row == 8);
+    }
+}
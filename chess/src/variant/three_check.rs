@@ -0,0 +1,19 @@
+use crate::{Color, Game};
+
+/* Three-check.
+ *
+ * Besides capturing the king, a side wins by giving check for the third
+ * time. Checks are counted in `Game::update_state` and written to FEN as
+ * the checks each side has left, e.g. "3+3" at the start.
+ */
+
+// Checks which win a game of Three-check
+pub(crate) const CHECKS_TO_WIN: u32 = 3;
+
+impl Game {
+
+    // Whether the opponent of the color has given the winning check
+    pub(crate) fn checks_exhausted(&self, _color: &Color) -> bool {
+        return self.get_checks(&_color.opponent()) >= CHECKS_TO_WIN;
+    }
+}
@@ -0,0 +1,177 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Color, Game, GameState, Role};
+
+/* JavaScript bindings, enabled with the "wasm" feature.
+ *
+ *   const game = new Game();
+ *   game.makeMove("e2", "e4");   // true
+ *   game.legalMoves("g8");       // ["h6", "f6"]
+ *   game.fen();
+ *   console.log(game.render());
+ */
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT: &'static str = r#"
+export type GameState = "in_progress" | "promotion" | "check" | "game_over" | "duck_placement";
+export type Color = "white" | "black";
+"#;
+
+/// A game of Chess royale.
+#[wasm_bindgen(js_name = Game)]
+pub struct WasmGame {
+    game: Game
+}
+
+#[wasm_bindgen(js_class = Game)]
+impl WasmGame {
+
+    /// Start a game from the initial position.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmGame {
+        return WasmGame { game: Game::new() };
+    }
+
+    /// Set up a game from Forsyth-Edwards Notation, undefined if it is not valid.
+    #[wasm_bindgen(js_name = fromFen)]
+    pub fn from_fen(fen: &str) -> Option<WasmGame> {
+        return Game::from_fen(fen).ok().map(|game| WasmGame { game });
+    }
+
+    /// Move a piece, e.g. `makeMove("e2", "e4")`. Returns false if the move was rejected.
+    #[wasm_bindgen(js_name = makeMove)]
+    pub fn make_move(&mut self, from: &str, to: &str) -> bool {
+        return self.game.try_make_move(from, to).is_ok();
+    }
+
+    /// Promote the pawn on `square` to "q", "r", "b" or "n". Returns false if not allowed.
+    #[wasm_bindgen(js_name = setPromotion)]
+    pub fn set_promotion(&mut self, square: &str, role: &str) -> bool {
+        let role: Role = match role.chars().next().and_then(Role::from_char) {
+            Some(role) => role,
+            None => return false
+        };
+
+        return self.game.try_set_promotion(square, role).is_ok();
+    }
+
+    /// Put the duck on `square` after a move in Duck chess. Returns false if it may not go there.
+    #[wasm_bindgen(js_name = placeDuck)]
+    pub fn place_duck(&mut self, square: &str) -> bool {
+        return self.game.try_place_duck(square).is_ok();
+    }
+
+    /// Squares the duck can be put on, empty unless the state is "duck_placement".
+    #[wasm_bindgen(js_name = duckSquares)]
+    pub fn duck_squares(&self) -> Vec<String> {
+        return self.game.get_duck_squares().iter().map(|position| position.to_string()).collect();
+    }
+
+    /// Squares the piece on `square` can move to, empty if there is no piece.
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self, square: &str) -> Vec<String> {
+        match self.game.get_possible_moves(square.to_string()) {
+            Some(moves) => return moves.iter().map(|position| position.to_string()).collect(),
+            None => return Vec::new()
+        }
+    }
+
+    /// The position in Forsyth-Edwards Notation.
+    pub fn fen(&self) -> String {
+        return self.game.to_fen();
+    }
+
+    /// The game in Portable Game Notation.
+    pub fn pgn(&self) -> String {
+        return self.game.to_pgn();
+    }
+
+    /// The current game state.
+    #[wasm_bindgen(unchecked_return_type = "GameState")]
+    pub fn state(&self) -> String {
+        let state: &str = match self.game.get_game_state() {
+            GameState::InProgress => "in_progress",
+            GameState::Promotion => "promotion",
+            GameState::Check => "check",
+            GameState::GameOver => "game_over",
+            GameState::DuckPlacement => "duck_placement"
+        };
+        return state.to_string();
+    }
+
+    /// Checks given by a color, counted in Three-check.
+    pub fn checks(&self, #[wasm_bindgen(unchecked_param_type = "Color")] color: &str) -> u32 {
+        match color {
+            "black" => return self.game.get_checks(&Color::Black),
+            _ => return self.game.get_checks(&Color::White)
+        }
+    }
+
+    /// The color whose turn it is.
+    #[wasm_bindgen(js_name = activeColor, unchecked_return_type = "Color")]
+    pub fn active_color(&self) -> String {
+        match self.game.get_active_color() {
+            Color::White => return "white".to_string(),
+            Color::Black => return "black".to_string()
+        }
+    }
+
+    /// The board as text, see the `Debug` output of `Game`.
+    pub fn render(&self) -> String {
+        return format!("{:?}", self.game);
+    }
+}
+
+impl Default for WasmGame {
+    fn default() -> Self {
+        return WasmGame::new();
+    }
+}
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+// Run with `wasm-pack test --node -- --features wasm`
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::WasmGame;
+
+    // check that moves are validated and reflected in the FEN
+    #[wasm_bindgen_test]
+    fn move_and_fen() {
+        let mut game = WasmGame::new();
+
+        assert!(!game.make_move("e2", "e5"));
+        assert!(game.make_move("e2", "e4"));
+        assert_eq!(game.fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+        assert_eq!(game.active_color(), "black");
+        assert_eq!(game.state(), "in_progress");
+    }
+
+    // check that duck chess waits for the duck and lists its squares
+    #[wasm_bindgen_test]
+    fn duck_placement() {
+        let mut game = WasmGame::from_fen("4k3/8/8/8/8/8/8/*3K3 w - - 0 1").unwrap();
+
+        assert!(game.make_move("e1", "e2"));
+        assert_eq!(game.state(), "duck_placement");
+        assert_eq!(game.duck_squares().len(), 61);
+        assert!(!game.make_move("e8", "e7"));
+        assert!(game.place_duck("d7"));
+        assert!(game.make_move("e8", "e7"));
+    }
+
+    // check legal moves for a knight and an empty square
+    #[wasm_bindgen_test]
+    fn legal_moves() {
+        let game = WasmGame::new();
+
+        assert_eq!(game.legal_moves("b1").len(), 2);
+        assert!(game.legal_moves("e4").is_empty());
+    }
+}
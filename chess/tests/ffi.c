@@ -0,0 +1,97 @@
+/* Exercises the C interface. Built and run by tests/ffi.rs, exits with 0 on success. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "rasan_chess.h"
+
+#define CHECK(condition) \
+    do { \
+        if (!(condition)) { \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+            return 1; \
+        } \
+    } while (0)
+
+int main(void) {
+    char fen[100];
+    ChessSquare moves[27];
+    size_t count = 0;
+    ChessGameState state;
+    ChessColor color;
+
+    Game *game = chess_game_new();
+    CHECK(game != NULL);
+
+    /* Legal moves of the knight on b1 */
+    CHECK(chess_legal_moves(game, "b1", moves, 27, &count) == CHESS_STATUS_OK);
+    CHECK(count == 2);
+    CHECK(chess_legal_moves(game, "b1", moves, 1, &count) == CHESS_STATUS_BUFFER_TOO_SMALL);
+    CHECK(count == 2);
+    CHECK(chess_legal_moves(game, "e4", moves, 27, &count) == CHESS_STATUS_NO_PIECE);
+
+    /* Rejected moves say why */
+    CHECK(chess_make_move(game, "e7", "e5") == CHESS_STATUS_NOT_YOUR_TURN);
+    CHECK(chess_make_move(game, "e2", "e5") == CHESS_STATUS_ILLEGAL_MOVE);
+    CHECK(chess_make_move(game, "z9", "e4") == CHESS_STATUS_INVALID_POSITION);
+    CHECK(chess_make_move(NULL, "e2", "e4") == CHESS_STATUS_NULL_POINTER);
+
+    CHECK(chess_make_move(game, "e2", "e4") == CHESS_STATUS_OK);
+    CHECK(chess_active_color(game, &color) == CHESS_STATUS_OK);
+    CHECK(color == CHESS_COLOR_BLACK);
+
+    CHECK(chess_to_fen(game, fen, 10) == CHESS_STATUS_BUFFER_TOO_SMALL);
+    CHECK(chess_fen_length(game, &count) == CHESS_STATUS_OK);
+    CHECK(count == strlen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1") + 1);
+    CHECK(chess_to_fen(game, fen, sizeof(fen)) == CHESS_STATUS_OK);
+    CHECK(strcmp(fen, "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1") == 0);
+    chess_game_free(game);
+
+    /* A pawn about to promote */
+    game = NULL;
+    CHECK(chess_game_from_fen("not a fen", &game) == CHESS_STATUS_INVALID_FEN);
+    CHECK(chess_game_from_fen("3k4/P7/8/8/8/8/8/4K3 w - - 0 1", &game) == CHESS_STATUS_OK);
+    CHECK(chess_make_move(game, "a7", "a8") == CHESS_STATUS_OK);
+    CHECK(chess_game_state(game, &state) == CHESS_STATUS_OK);
+    CHECK(state == CHESS_GAME_STATE_PROMOTION);
+    CHECK(chess_make_move(game, "d8", "d7") == CHESS_STATUS_PROMOTION_PENDING);
+    CHECK(chess_set_promotion(game, "a8", CHESS_ROLE_KING) == CHESS_STATUS_PROMOTION_NOT_ALLOWED);
+    CHECK(chess_set_promotion(game, "a8", 11) == CHESS_STATUS_INVALID_ARGUMENT);
+    CHECK(chess_set_promotion(game, "a8", 0xFFFFFFFFu) == CHESS_STATUS_INVALID_ARGUMENT);
+    CHECK(chess_set_promotion(game, "a8", CHESS_ROLE_QUEEN) == CHESS_STATUS_OK);
+    CHECK(chess_to_fen(game, fen, sizeof(fen)) == CHESS_STATUS_OK);
+    CHECK(strcmp(fen, "Q2k4/8/8/8/8/8/8/4K3 b - - 0 1") == 0);
+    chess_game_free(game);
+
+    /* Three-check counts the checks of both colors */
+    unsigned int checks = 0;
+    CHECK(chess_game_from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +1+0", &game) == CHESS_STATUS_OK);
+    CHECK(chess_make_move(game, "a1", "a8") == CHESS_STATUS_OK);
+    CHECK(chess_game_state(game, &state) == CHESS_STATUS_OK);
+    CHECK(state == CHESS_GAME_STATE_CHECK);
+    CHECK(chess_checks(game, CHESS_COLOR_WHITE, &checks) == CHESS_STATUS_OK);
+    CHECK(checks == 2);
+    CHECK(chess_checks(game, CHESS_COLOR_BLACK, &checks) == CHESS_STATUS_OK);
+    CHECK(checks == 0);
+    CHECK(chess_checks(game, 2, &checks) == CHESS_STATUS_INVALID_ARGUMENT);
+    chess_game_free(game);
+
+    /* Duck chess waits for the duck after every move */
+    ChessSquare squares[64];
+    CHECK(chess_game_from_fen("4k3/8/8/8/8/8/8/*3K3 w - - 0 1", &game) == CHESS_STATUS_OK);
+    CHECK(chess_make_move(game, "e1", "e2") == CHESS_STATUS_OK);
+    CHECK(chess_game_state(game, &state) == CHESS_STATUS_OK);
+    CHECK(state == CHESS_GAME_STATE_DUCK_PLACEMENT);
+    CHECK(chess_make_move(game, "e8", "e7") == CHESS_STATUS_DUCK_PENDING);
+    CHECK(chess_duck_squares(game, squares, 64, &count) == CHESS_STATUS_OK);
+    CHECK(count == 61);
+    CHECK(chess_place_duck(game, "e2") == CHESS_STATUS_ILLEGAL_MOVE);
+    CHECK(chess_place_duck(game, "d7") == CHESS_STATUS_OK);
+    CHECK(chess_duck_squares(game, squares, 64, &count) == CHESS_STATUS_OK);
+    CHECK(count == 0);
+    CHECK(chess_make_move(game, "e8", "e7") == CHESS_STATUS_OK);
+    chess_game_free(game);
+
+    chess_game_free(NULL);
+    return 0;
+}
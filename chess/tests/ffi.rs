@@ -0,0 +1,40 @@
+// Builds the static library with the "ffi" feature, compiles tests/ffi.c
+// against it and the generated header, and runs the C program.
+#![cfg(feature = "ffi")]
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn c_program() {
+    let manifest_dir: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    // The test binary lives in target/<profile>/deps
+    let exe: PathBuf = env::current_exe().unwrap();
+    let profile_dir: PathBuf = exe.parent().unwrap().parent().unwrap().to_path_buf();
+
+    // cargo test does not produce the static library itself
+    let cargo: String = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let mut build = Command::new(cargo);
+    build.args(["build", "--lib", "--features", "ffi", "--manifest-path"]).arg(manifest_dir.join("Cargo.toml"));
+    if profile_dir.ends_with("release") {
+        build.arg("--release");
+    }
+    assert!(build.status().unwrap().success());
+
+    let program: PathBuf = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi_test");
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| String::from("cc")))
+        .arg(manifest_dir.join("tests/ffi.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(profile_dir.join("librasan_chess.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let status = Command::new(&program).status().unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,332 @@
+// The code base prefers explicit returns over the terser forms clippy suggests,
+// and the pyo3 macros convert errors clippy considers already converted
+#![allow(clippy::needless_return, clippy::useless_conversion)]
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use rasan_chess as chess;
+
+/* Python bindings, build with `maturin develop` in this directory.
+ *
+ *   >>> import rasan_chess
+ *   >>> game = rasan_chess.Game()
+ *   >>> game.make_move("e2", "e4")
+ *   GameState.IN_PROGRESS
+ *   >>> for origin, target in game.legal_moves():
+ *   ...     print(origin, target)
+ */
+
+#[pyclass(eq, eq_int, module = "rasan_chess")]
+#[derive(Copy, Clone, PartialEq)]
+pub enum Color {
+    #[pyo3(name = "WHITE")]
+    White,
+    #[pyo3(name = "BLACK")]
+    Black
+}
+
+#[pyclass(eq, eq_int, module = "rasan_chess")]
+#[derive(Copy, Clone, PartialEq)]
+pub enum Role {
+    #[pyo3(name = "KING")]
+    King,
+    #[pyo3(name = "QUEEN")]
+    Queen,
+    #[pyo3(name = "ROOK")]
+    Rook,
+    #[pyo3(name = "BISHOP")]
+    Bishop,
+    #[pyo3(name = "KNIGHT")]
+    Knight,
+    #[pyo3(name = "PAWN")]
+    Pawn,
+    #[pyo3(name = "ARCHBISHOP")]
+    Archbishop,
+    #[pyo3(name = "CHANCELLOR")]
+    Chancellor,
+    #[pyo3(name = "AMAZON")]
+    Amazon,
+    #[pyo3(name = "NIGHTRIDER")]
+    Nightrider,
+    #[pyo3(name = "GRASSHOPPER")]
+    Grasshopper
+}
+
+#[pyclass(eq, eq_int, module = "rasan_chess")]
+#[derive(Copy, Clone, PartialEq)]
+pub enum GameState {
+    #[pyo3(name = "IN_PROGRESS")]
+    InProgress,
+    #[pyo3(name = "PROMOTION")]
+    Promotion,
+    #[pyo3(name = "CHECK")]
+    Check,
+    #[pyo3(name = "GAME_OVER")]
+    GameOver,
+    #[pyo3(name = "DUCK_PLACEMENT")]
+    DuckPlacement
+}
+
+/// A square on the board, created from its name, e.g. Position("e4").
+#[pyclass(frozen, module = "rasan_chess")]
+#[derive(Clone)]
+pub struct Position {
+    inner: chess::Position
+}
+
+/// A game of Chess royale.
+#[pyclass(module = "rasan_chess")]
+pub struct Game {
+    inner: chess::Game
+}
+
+impl From<chess::Color> for Color {
+    fn from(color: chess::Color) -> Self {
+        match color {
+            chess::Color::White => Color::White,
+            chess::Color::Black => Color::Black
+        }
+    }
+}
+
+impl From<Color> for chess::Color {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::White => chess::Color::White,
+            Color::Black => chess::Color::Black
+        }
+    }
+}
+
+// Roles in the order of chess::ROLES
+const ROLES: [Role; 11] = [
+    Role::King, Role::Queen, Role::Rook, Role::Bishop, Role::Knight, Role::Pawn,
+    Role::Archbishop, Role::Chancellor, Role::Amazon, Role::Nightrider, Role::Grasshopper
+];
+
+impl From<chess::Role> for Role {
+    fn from(role: chess::Role) -> Self {
+        return ROLES[role.index()];
+    }
+}
+
+impl From<Role> for chess::Role {
+    fn from(role: Role) -> Self {
+        return chess::ROLES[role as usize].role.clone();
+    }
+}
+
+impl From<chess::GameState> for GameState {
+    fn from(state: chess::GameState) -> Self {
+        match state {
+            chess::GameState::InProgress => GameState::InProgress,
+            chess::GameState::Promotion => GameState::Promotion,
+            chess::GameState::Check => GameState::Check,
+            chess::GameState::GameOver => GameState::GameOver,
+            chess::GameState::DuckPlacement => GameState::DuckPlacement
+        }
+    }
+}
+
+#[pymethods]
+impl Position {
+
+    #[new]
+    fn new(name: &str) -> PyResult<Self> {
+        match chess::Position::parse(name) {
+            Some(inner) => return Ok(Position { inner }),
+            None => return Err(PyValueError::new_err(format!("Not a valid position: {}", name)))
+        }
+    }
+
+    /// Column number, 1 is the a-file.
+    #[getter]
+    fn column(&self) -> i8 {
+        return self.inner.column;
+    }
+
+    /// Row number, 1 is white's back rank.
+    #[getter]
+    fn row(&self) -> i8 {
+        return self.inner.row;
+    }
+
+    fn __str__(&self) -> String {
+        return self.inner.to_string();
+    }
+
+    fn __repr__(&self) -> String {
+        return format!("Position('{}')", self.inner);
+    }
+
+    fn __eq__(&self, other: &Position) -> bool {
+        return self.inner == other.inner;
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.inner.hash(&mut hasher);
+        return hasher.finish();
+    }
+}
+
+#[pymethods]
+impl Game {
+
+    #[new]
+    fn new() -> Self {
+        return Game { inner: chess::Game::new() };
+    }
+
+    /// Set up a game from Forsyth-Edwards Notation.
+    #[staticmethod]
+    fn from_fen(fen: &str) -> PyResult<Self> {
+        match chess::Game::from_fen(fen) {
+            Ok(inner) => return Ok(Game { inner }),
+            Err(error) => return Err(PyValueError::new_err(error.to_string()))
+        }
+    }
+
+    /// Move a piece. Squares are names like "e2" or Positions.
+    /// Raises ValueError if the move is not allowed.
+    fn make_move(&mut self, origin: &Bound<'_, PyAny>, target: &Bound<'_, PyAny>) -> PyResult<GameState> {
+        let origin: String = square_name(origin)?;
+        let target: String = square_name(target)?;
+
+        match self.inner.try_make_move(&origin, &target) {
+            Ok(state) => return Ok(state.into()),
+            Err(error) => return Err(PyValueError::new_err(error.to_string()))
+        }
+    }
+
+    /// Choose what the pawn on the last rank becomes.
+    fn set_promotion(&mut self, square: &Bound<'_, PyAny>, role: Role) -> PyResult<GameState> {
+        let square: String = square_name(square)?;
+
+        match self.inner.try_set_promotion(&square, role.into()) {
+            Ok(state) => return Ok(state.into()),
+            Err(error) => return Err(PyValueError::new_err(error.to_string()))
+        }
+    }
+
+    /// Put the duck on an empty square after a move in Duck chess.
+    /// Raises ValueError if the duck may not go there.
+    fn place_duck(&mut self, square: &Bound<'_, PyAny>) -> PyResult<GameState> {
+        let square: String = square_name(square)?;
+
+        match self.inner.try_place_duck(&square) {
+            Ok(state) => return Ok(state.into()),
+            Err(error) => return Err(PyValueError::new_err(error.to_string()))
+        }
+    }
+
+    /// Squares the duck can be put on, empty unless the state is DUCK_PLACEMENT.
+    fn duck_squares(&self) -> Vec<Position> {
+        return self.inner.get_duck_squares().into_iter().map(|inner| Position { inner }).collect();
+    }
+
+    /// Square of the duck in Duck chess, otherwise None.
+    #[getter]
+    fn duck(&self) -> Option<Position> {
+        return self.inner.get_duck().map(|inner| Position { inner });
+    }
+
+    /// Give up the game for a color.
+    fn resign(&mut self, color: Color) -> GameState {
+        return self.inner.resign(color.into()).into();
+    }
+
+    /// Moves of the color to move as (from, to) pairs. With a square,
+    /// only the targets of the piece standing there.
+    #[pyo3(signature = (square = None))]
+    fn legal_moves(&self, square: Option<&Bound<'_, PyAny>>) -> PyResult<Vec<(Position, Position)>> {
+        let moves: Vec<(chess::Position, chess::Position)> = match square {
+            Some(square) => {
+                let origin: chess::Position = parse(&square_name(square)?)?;
+                match self.inner.get_possible_moves(origin.to_string()) {
+                    Some(targets) => targets.into_iter().map(|target| (origin.clone(), target)).collect(),
+                    None => Vec::new()
+                }
+            },
+            None => self.inner.get_legal_moves()
+        };
+
+        return Ok(moves.into_iter().map(|(origin, target)| (Position { inner: origin }, Position { inner: target })).collect());
+    }
+
+    /// Color and role of the piece on a square, or None.
+    fn piece_at(&self, square: &Bound<'_, PyAny>) -> PyResult<Option<(Color, Role)>> {
+        let position: chess::Position = parse(&square_name(square)?)?;
+        return Ok(self.inner.get_piece_at(&position).map(|(color, role)| (color.into(), role.into())));
+    }
+
+    #[getter]
+    fn state(&self) -> GameState {
+        return self.inner.get_game_state().into();
+    }
+
+    #[getter]
+    fn active_color(&self) -> Color {
+        return self.inner.get_active_color().into();
+    }
+
+    /// Checks given by white and black, counted in Three-check.
+    #[getter]
+    fn checks(&self) -> (u32, u32) {
+        return (self.inner.get_checks(&chess::Color::White), self.inner.get_checks(&chess::Color::Black));
+    }
+
+    /// The winning color once the game is over, otherwise None.
+    #[getter]
+    fn winner(&self) -> Option<Color> {
+        return self.inner.get_winner().map(Color::from);
+    }
+
+    /// Moves played so far in algebraic notation.
+    #[getter]
+    fn history(&self) -> Vec<String> {
+        return self.inner.get_history();
+    }
+
+    fn fen(&self) -> String {
+        return self.inner.to_fen();
+    }
+
+    fn pgn(&self) -> String {
+        return self.inner.to_pgn();
+    }
+
+    fn __repr__(&self) -> String {
+        return format!("{:?}", self.inner);
+    }
+}
+
+// Accept both square names and Positions
+fn square_name(square: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(position) = square.downcast::<Position>() {
+        return Ok(position.get().inner.to_string());
+    }
+    return square.extract::<String>();
+}
+
+fn parse(name: &str) -> PyResult<chess::Position> {
+    match chess::Position::parse(name) {
+        Some(position) => return Ok(position),
+        None => return Err(PyValueError::new_err(format!("Not a valid position: {}", name)))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "rasan_chess")]
+fn rasan_chess_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Game>()?;
+    m.add_class::<Position>()?;
+    m.add_class::<Color>()?;
+    m.add_class::<Role>()?;
+    m.add_class::<GameState>()?;
+    return Ok(());
+}
@@ -0,0 +1,60 @@
+# Run with `maturin develop && pytest` in the python directory.
+
+import pytest
+
+from rasan_chess import Color, Game, GameState, Position, Role
+
+
+def test_moves_and_fen():
+    game = Game()
+    assert game.make_move("e2", Position("e4")) == GameState.IN_PROGRESS
+    assert game.active_color == Color.BLACK
+    assert game.fen() == "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+    assert game.piece_at("e4") == (Color.WHITE, Role.PAWN)
+
+    with pytest.raises(ValueError, match="Not your turn"):
+        game.make_move("d2", "d4")
+
+
+def test_move_iteration():
+    game = Game()
+    moves = game.legal_moves()
+    assert len(moves) == 20
+    assert (Position("g1"), Position("f3")) in moves
+    assert {str(target) for _, target in game.legal_moves("b1")} == {"a3", "c3"}
+
+
+def test_promotion_and_pgn():
+    game = Game.from_fen("3k4/P7/8/8/8/8/8/4K3 w - - 0 1")
+    assert game.make_move("a7", "a8") == GameState.PROMOTION
+    assert game.set_promotion("a8", Role.QUEEN) == GameState.IN_PROGRESS
+    assert game.history == ["a8=Q"]
+
+    game.resign(Color.BLACK)
+    assert game.winner == Color.WHITE
+    assert game.pgn().endswith("1. a8=Q 1-0\n")
+
+
+def test_three_check_counters():
+    game = Game.from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1 +1+0")
+    assert game.checks == (1, 0)
+    assert game.make_move("a1", "a8") == GameState.CHECK
+    assert game.checks == (2, 0)
+
+
+def test_duck_placement():
+    game = Game.from_fen("4k3/8/8/8/8/8/8/*3K3 w - - 0 1")
+    assert game.make_move("e1", "e2") == GameState.DUCK_PLACEMENT
+    assert Position("a1") not in game.duck_squares()
+    assert len(game.duck_squares()) == 61
+
+    with pytest.raises(ValueError, match="Waiting for the duck"):
+        game.make_move("e8", "e7")
+
+    assert game.place_duck("d7") == GameState.IN_PROGRESS
+    assert game.duck == Position("d7")
+    assert game.active_color == Color.BLACK
+
+
+def test_repr_renders_board():
+    assert repr(Game()).splitlines()[0].split() == ["R", "Kn", "B", "Q", "K", "B", "Kn", "R"]
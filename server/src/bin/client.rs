@@ -0,0 +1,128 @@
+// The code base prefers explicit returns over the terser forms clippy suggests
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::io::{self, BufRead};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::Message;
+
+use rasan_chess_server::protocol::{ClientMessage, PromotionRole, Seat};
+
+/* Small interactive client for trying the server on localhost.
+ *
+ * Reads one command per line from stdin and prints every message the
+ * server sends. Commands:
+ *
+ *   create [game]
+ *   join <game> <white|black|spectator> [token]
+ *   move <from> <to>
+ *   promote <square> <queen|rook|bishop|knight>
+ *   resign
+ *   state
+ *
+ * Lines starting with '{' are sent as raw JSON.
+ */
+
+const DEFAULT_URL: &str = "ws://127.0.0.1:9001";
+
+fn main() {
+    let url: String = env::args().nth(1).unwrap_or_else(|| String::from(DEFAULT_URL));
+
+    let (mut socket, _) = match tungstenite::connect(&url) {
+        Ok(connection) => connection,
+        Err(error) => {
+            eprintln!("Could not connect to {}: {}", url, error);
+            std::process::exit(1);
+        }
+    };
+
+    // Poll the socket so typed commands are sent without waiting for the server
+    if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        let stream: &TcpStream = stream;
+        stream.set_read_timeout(Some(Duration::from_millis(20))).unwrap();
+    }
+
+    // Read stdin on its own thread
+    let (lines, commands) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            match line {
+                Ok(line) => if lines.send(line).is_err() { return },
+                Err(_) => return
+            }
+        }
+    });
+
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => println!("< {}", text),
+            Ok(Message::Close(_)) => return,
+            Ok(_) => (),
+            Err(tungstenite::Error::Io(ref error)) if error.kind() == io::ErrorKind::WouldBlock || error.kind() == io::ErrorKind::TimedOut => (),
+            Err(error) => {
+                eprintln!("Connection lost: {}", error);
+                return;
+            }
+        }
+
+        for line in commands.try_iter() {
+            let text: String = if line.trim_start().starts_with('{') {
+                line
+            } else {
+                match parse_command(&line) {
+                    Some(message) => serde_json::to_string(&message).unwrap(),
+                    None => {
+                        eprintln!("Unknown command: {}", line);
+                        continue;
+                    }
+                }
+            };
+
+            if let Err(error) = socket.send(Message::Text(text)) {
+                eprintln!("Connection lost: {}", error);
+                return;
+            }
+        }
+    }
+}
+
+// Turn a typed command into a protocol message
+fn parse_command(line: &str) -> Option<ClientMessage> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["create"] => return Some(ClientMessage::Create { game: None }),
+        ["create", game] => return Some(ClientMessage::Create { game: Some(game.to_string()) }),
+        ["join", game, seat] => return Some(ClientMessage::Join { game: game.to_string(), seat: parse_seat(seat)?, token: None }),
+        ["join", game, seat, token] => return Some(ClientMessage::Join { game: game.to_string(), seat: parse_seat(seat)?, token: Some(token.to_string()) }),
+        ["move", from, to] => return Some(ClientMessage::Move { from: from.to_string(), to: to.to_string() }),
+        ["promote", square, role] => return Some(ClientMessage::Promote { square: square.to_string(), role: parse_role(role)? }),
+        ["resign"] => return Some(ClientMessage::Resign),
+        ["state"] => return Some(ClientMessage::State),
+        _ => return None
+    }
+}
+
+fn parse_seat(seat: &str) -> Option<Seat> {
+    match seat {
+        "white" => return Some(Seat::White),
+        "black" => return Some(Seat::Black),
+        "spectator" => return Some(Seat::Spectator),
+        _ => return None
+    }
+}
+
+fn parse_role(role: &str) -> Option<PromotionRole> {
+    match role {
+        "queen" => return Some(PromotionRole::Queen),
+        "rook" => return Some(PromotionRole::Rook),
+        "bishop" => return Some(PromotionRole::Bishop),
+        "knight" => return Some(PromotionRole::Knight),
+        _ => return None
+    }
+}
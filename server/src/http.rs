@@ -0,0 +1,229 @@
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use rasan_chess::{Color, Game, Position};
+
+use crate::lobby::Lobby;
+use crate::protocol::{PlayerColor, PromotionRole};
+
+/* HTTP JSON API for scripts and web frontends.
+ *
+ *   POST /games                      {"game": "final"}           create a game
+ *   GET  /games/{id}                                             board, FEN, state and checks
+ *   GET  /games/{id}/moves/{square}                              possible moves of a piece
+ *   POST /games/{id}/moves           {"from": "e2", "to": "e4"}  play a move
+ *   POST /games/{id}/promotion       {"square": "e8", "role": "queen"}
+ *   POST /games/{id}/resign          {"color": "white"}
+ *   GET  /games/{id}/pgn                                         game in PGN
+ *
+ * Games are shared with the WebSocket server, so moves made here are
+ * broadcast to players and spectators as well. Once a player holds a seat,
+ * moves, promotions and resignations need the seat token handed out on
+ * joining, e.g. {"from": "e2", "to": "e4", "token": "..."}, and are made
+ * for that seat only. Games nobody is seated at are played without one.
+ */
+
+#[derive(Deserialize, Default)]
+struct CreateRequest {
+    #[serde(default)]
+    game: Option<String>
+}
+
+#[derive(Deserialize)]
+struct MoveRequest {
+    from: String,
+    to: String,
+    #[serde(default)]
+    token: Option<String>
+}
+
+#[derive(Deserialize)]
+struct PromotionRequest {
+    square: String,
+    role: PromotionRole,
+    #[serde(default)]
+    token: Option<String>
+}
+
+#[derive(Deserialize)]
+struct ResignRequest {
+
+    // Needed without a token, the seat of the token otherwise
+    #[serde(default)]
+    color: Option<PlayerColor>,
+    #[serde(default)]
+    token: Option<String>
+}
+
+// Status code and JSON body of a response
+type Reply = (u16, Value);
+
+/// Answer HTTP requests until the server shuts down.
+pub fn serve(server: Server, lobby: Arc<Mutex<Lobby>>) {
+    for mut request in server.incoming_requests() {
+        let mut body: String = String::new();
+        let (status, json): Reply = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => route(&lobby, request.method(), request.url(), &body),
+            Err(_) => error(400, "Body is not valid UTF-8")
+        };
+
+        respond(request, status, json);
+    }
+}
+
+fn respond(request: Request, status: u16, json: Value) {
+    let header: Header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let response = Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(header);
+
+    // The client may have hung up, nothing to do then
+    let _ = request.respond(response);
+}
+
+// Dispatch a request on method and path
+fn route(lobby: &Mutex<Lobby>, method: &Method, url: &str, body: &str) -> Reply {
+    let path: &str = url.split('?').next().unwrap_or("");
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    let mut lobby = lobby.lock().unwrap();
+
+    match (method, segments.as_slice()) {
+        (Method::Post, ["games"]) => {
+
+            // An empty body creates a game with a generated id
+            let request: CreateRequest = if body.trim().is_empty() {
+                CreateRequest::default()
+            } else {
+                match parse(body) {
+                    Ok(request) => request,
+                    Err(reply) => return reply
+                }
+            };
+
+            match lobby.create_game(request.game) {
+                Ok(game) => return (201, json!({ "game": game })),
+                Err(message) => return error(409, &message)
+            }
+        },
+        (Method::Get, ["games", game]) => {
+            match lobby.snapshot(game) {
+                Ok(snapshot) => return (200, json!(snapshot)),
+                Err(message) => return error(404, &message)
+            }
+        },
+        (Method::Get, ["games", game, "moves", square]) => {
+            let game: &Game = match find(&lobby, game) {
+                Ok(game) => game,
+                Err(reply) => return reply
+            };
+
+            match game.get_possible_moves(square.to_string()) {
+                Some(moves) => {
+                    let moves: Vec<String> = moves.iter().map(Position::to_string).collect();
+                    return (200, json!({ "square": square, "moves": moves }));
+                },
+                None => return error(404, &format!("No piece on {}", square))
+            }
+        },
+        (Method::Post, ["games", game, "moves"]) => {
+            if let Err(reply) = find(&lobby, game) {
+                return reply;
+            }
+            let request: MoveRequest = match parse(body) {
+                Ok(request) => request,
+                Err(reply) => return reply
+            };
+
+            let seat: Option<Color> = match authorize(&lobby, game, &request.token) {
+                Ok(seat) => seat,
+                Err(reply) => return reply
+            };
+
+            match lobby.play(game, seat, request.from, request.to) {
+                Ok(_) => return (200, json!(lobby.snapshot(game).unwrap())),
+                Err(message) => return error(409, &message)
+            }
+        },
+        (Method::Post, ["games", game, "promotion"]) => {
+            if let Err(reply) = find(&lobby, game) {
+                return reply;
+            }
+            let request: PromotionRequest = match parse(body) {
+                Ok(request) => request,
+                Err(reply) => return reply
+            };
+
+            let seat: Option<Color> = match authorize(&lobby, game, &request.token) {
+                Ok(seat) => seat,
+                Err(reply) => return reply
+            };
+
+            match lobby.promote_pawn(game, seat, request.square, request.role) {
+                Ok(_) => return (200, json!(lobby.snapshot(game).unwrap())),
+                Err(message) => return error(409, &message)
+            }
+        },
+        (Method::Post, ["games", game, "resign"]) => {
+            if let Err(reply) = find(&lobby, game) {
+                return reply;
+            }
+            let request: ResignRequest = match parse(body) {
+                Ok(request) => request,
+                Err(reply) => return reply
+            };
+
+            let seat: Option<Color> = match authorize(&lobby, game, &request.token) {
+                Ok(seat) => seat,
+                Err(reply) => return reply
+            };
+            let color: Color = match (seat, request.color.map(Color::from)) {
+                (Some(seat), None) => seat,
+                (Some(seat), Some(color)) if seat == color => seat,
+                (Some(_), Some(_)) => return error(403, "Not your seat"),
+                (None, Some(color)) => color,
+                (None, None) => return error(400, "Invalid request: missing field `color`")
+            };
+
+            match lobby.resign_game(game, color) {
+                Ok(_) => return (200, json!(lobby.snapshot(game).unwrap())),
+                Err(message) => return error(409, &message)
+            }
+        },
+        (Method::Get, ["games", game, "pgn"]) => {
+            match find(&lobby, game) {
+                Ok(game) => return (200, json!({ "pgn": game.to_pgn() })),
+                Err(reply) => return reply
+            }
+        },
+        _ => return error(404, &format!("No route for {} {}", method, path))
+    }
+}
+
+fn find<'a>(lobby: &'a Lobby, game: &str) -> Result<&'a Game, Reply> {
+    match lobby.game(game) {
+        Some(game) => return Ok(game),
+        None => return Err(error(404, &format!("No game with id {}", game)))
+    }
+}
+
+// Seat of the token, refused with 403
+fn authorize(lobby: &Lobby, game: &str, token: &Option<String>) -> Result<Option<Color>, Reply> {
+    match lobby.authorize(game, token.as_deref()) {
+        Ok(seat) => return Ok(seat),
+        Err(message) => return Err(error(403, &message))
+    }
+}
+
+fn parse<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, Reply> {
+    match serde_json::from_str(body) {
+        Ok(request) => return Ok(request),
+        Err(message) => return Err(error(400, &format!("Invalid request: {}", message)))
+    }
+}
+
+fn error(status: u16, message: &str) -> Reply {
+    return (status, json!({ "error": message }));
+}
@@ -0,0 +1,315 @@
+// The code base prefers explicit returns and matches over the terser forms clippy suggests
+#![allow(clippy::needless_return)]
+
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tungstenite::{Message, WebSocket};
+
+pub mod http;
+pub mod lobby;
+pub mod protocol;
+
+use lobby::{ClientId, Lobby};
+use protocol::{ClientMessage, ServerMessage};
+
+/* Networked referee for Chess royale.
+ *
+ * Every connection gets its own thread. All games live in a shared lobby,
+ * connections only translate between WebSocket frames and lobby messages.
+ */
+
+// How long a connection waits for a frame before flushing its outbox
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Accept WebSocket connections on the listener until it fails.
+pub fn serve(listener: TcpListener, lobby: Arc<Mutex<Lobby>>) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream: TcpStream = stream?;
+        let lobby: Arc<Mutex<Lobby>> = Arc::clone(&lobby);
+
+        thread::spawn(move || {
+            if let Err(error) = handle_connection(stream, lobby) {
+                println!("Connection closed: {}", error);
+            }
+        });
+    }
+
+    return Ok(());
+}
+
+// Pump messages between one socket and the lobby until the client leaves
+fn handle_connection(stream: TcpStream, lobby: Arc<Mutex<Lobby>>) -> Result<(), String> {
+    let mut socket: WebSocket<TcpStream> = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(error) => return Err(error.to_string())
+    };
+
+    // Reads time out regularly so broadcasts from other players get through
+    if let Err(error) = socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)) {
+        return Err(error.to_string());
+    }
+
+    let (outbox, inbox) = mpsc::channel::<ServerMessage>();
+    let client: ClientId = lobby.lock().unwrap().connect(outbox);
+
+    let result: Result<(), tungstenite::Error> = pump(&mut socket, client, &lobby, &inbox);
+    lobby.lock().unwrap().disconnect(client);
+    return result.map_err(|error| error.to_string());
+}
+
+// Tungstenite's error is large, but it only travels one frame up
+#[allow(clippy::result_large_err)]
+fn pump(socket: &mut WebSocket<TcpStream>, client: ClientId, lobby: &Mutex<Lobby>, inbox: &Receiver<ServerMessage>) -> Result<(), tungstenite::Error> {
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(message) => lobby.lock().unwrap().handle(client, message),
+                    Err(error) => {
+                        let reply: ServerMessage = ServerMessage::Error { message: format!("Invalid message: {}", error) };
+                        socket.send(Message::Text(serde_json::to_string(&reply).unwrap()))?;
+                    }
+                }
+            },
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => (),
+
+            // Nothing arrived in time
+            Err(tungstenite::Error::Io(ref error)) if error.kind() == io::ErrorKind::WouldBlock || error.kind() == io::ErrorKind::TimedOut => (),
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(error) => return Err(error)
+        }
+
+        // Forward everything the lobby has queued for this client
+        for message in inbox.try_iter() {
+            socket.send(Message::Text(serde_json::to_string(&message).unwrap()))?;
+        }
+    }
+}
+
+
+
+// --------------------------
+// ######### TESTS ##########
+// --------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use tungstenite::stream::MaybeTlsStream;
+    use tungstenite::{Message, WebSocket};
+
+    use super::lobby::Lobby;
+    use super::protocol::{Checks, ClientMessage, Seat, ServerMessage, Status};
+    use super::{http, serve};
+
+    type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+    // Start both servers on free ports sharing one lobby, return the WebSocket url and HTTP address
+    fn start_servers() -> (String, String) {
+        let lobby = Arc::new(Mutex::new(Lobby::new()));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let sockets = Arc::clone(&lobby);
+        thread::spawn(move || serve(listener, sockets));
+
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap().to_string();
+        thread::spawn(move || http::serve(server, lobby));
+
+        return (url, address);
+    }
+
+    fn start_server() -> String {
+        return start_servers().0;
+    }
+
+    // Minimal HTTP client, returns the status code and JSON body
+    fn request(address: &str, method: &str, path: &str, body: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", method, path, address, body.len(), body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status: u16 = response[9..12].parse().unwrap();
+        let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        return (status, serde_json::from_str(body).unwrap());
+    }
+
+    fn send(socket: &mut Socket, message: ClientMessage) {
+        socket.send(Message::Text(serde_json::to_string(&message).unwrap())).unwrap();
+    }
+
+    fn receive(socket: &mut Socket) -> ServerMessage {
+        loop {
+            if let Message::Text(text) = socket.read().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    // join a game and return the seat token, skipping the state snapshot
+    fn join(socket: &mut Socket, game: &str, seat: Seat, token: Option<String>) -> Option<String> {
+        send(socket, ClientMessage::Join { game: game.to_string(), seat, token });
+        let token = match receive(socket) {
+            ServerMessage::Joined { token, .. } => token,
+            other => panic!("unexpected {:?}", other)
+        };
+        assert!(matches!(receive(socket), ServerMessage::State { .. }));
+        return token;
+    }
+
+    // two players and a spectator see the same moves, illegal moves only reach the mover
+    #[test]
+    fn moves_are_broadcast() {
+        let url = start_server();
+        let (mut white, _) = tungstenite::connect(&url).unwrap();
+        let (mut black, _) = tungstenite::connect(&url).unwrap();
+        let (mut spectator, _) = tungstenite::connect(&url).unwrap();
+
+        send(&mut white, ClientMessage::Create { game: Some("final".to_string()) });
+        assert_eq!(receive(&mut white), ServerMessage::Created { game: "final".to_string() });
+
+        join(&mut white, "final", Seat::White, None);
+        join(&mut black, "final", Seat::Black, None);
+        join(&mut spectator, "final", Seat::Spectator, None);
+
+        send(&mut black, ClientMessage::Move { from: "e7".to_string(), to: "e5".to_string() });
+        assert!(matches!(receive(&mut black), ServerMessage::Error { .. }));
+
+        send(&mut white, ClientMessage::Move { from: "e2".to_string(), to: "e4".to_string() });
+        let moved = ServerMessage::Moved {
+            game: "final".to_string(),
+            color: super::protocol::PlayerColor::White,
+            from: "e2".to_string(),
+            to: "e4".to_string(),
+            state: Status::InProgress,
+            checks: Checks::default()
+        };
+        assert_eq!(receive(&mut white), moved);
+        assert_eq!(receive(&mut black), moved);
+        assert_eq!(receive(&mut spectator), moved);
+    }
+
+    // a dropped player reclaims the seat with the token
+    #[test]
+    fn player_reconnects_with_token() {
+        let url = start_server();
+        let (mut white, _) = tungstenite::connect(&url).unwrap();
+        let (mut black, _) = tungstenite::connect(&url).unwrap();
+
+        send(&mut white, ClientMessage::Create { game: None });
+        let game = match receive(&mut white) {
+            ServerMessage::Created { game } => game,
+            other => panic!("unexpected {:?}", other)
+        };
+
+        let token = join(&mut white, &game, Seat::White, None);
+        let other = join(&mut black, &game, Seat::Black, None);
+        assert_eq!(token.as_ref().map(String::len), Some(32));
+        assert_ne!(token, other);
+
+        white.close(None).unwrap();
+        while white.read().is_ok() {}
+        assert_eq!(receive(&mut black), ServerMessage::PlayerLeft { game: game.clone(), seat: Seat::White });
+
+        // someone else cannot take the seat
+        let (mut intruder, _) = tungstenite::connect(&url).unwrap();
+        send(&mut intruder, ClientMessage::Join { game: game.clone(), seat: Seat::White, token: None });
+        assert!(matches!(receive(&mut intruder), ServerMessage::Error { .. }));
+
+        let (mut white, _) = tungstenite::connect(&url).unwrap();
+        assert_eq!(join(&mut white, &game, Seat::White, token.clone()), token);
+        assert_eq!(receive(&mut black), ServerMessage::PlayerRejoined { game: game.clone(), seat: Seat::White });
+
+        // a failed join keeps the seat the player has
+        send(&mut black, ClientMessage::Join { game: game.clone(), seat: Seat::White, token: None });
+        assert!(matches!(receive(&mut black), ServerMessage::Error { .. }));
+        send(&mut white, ClientMessage::Move { from: "e2".to_string(), to: "e4".to_string() });
+        assert_eq!(receive(&mut white), ServerMessage::PlayerRejoined { game: game.clone(), seat: Seat::White });
+        assert!(matches!(receive(&mut white), ServerMessage::Moved { .. }));
+        assert!(matches!(receive(&mut black), ServerMessage::Moved { .. }));
+        send(&mut black, ClientMessage::Move { from: "e7".to_string(), to: "e5".to_string() });
+        assert!(matches!(receive(&mut black), ServerMessage::Moved { .. }));
+    }
+
+    // a game driven over HTTP is seen by WebSocket spectators
+    #[test]
+    fn http_api_drives_game() {
+        let (url, address) = start_servers();
+
+        let (status, body) = request(&address, "POST", "/games", r#"{"game": "club"}"#);
+        assert_eq!((status, body["game"].as_str()), (201, Some("club")));
+
+        let (mut spectator, _) = tungstenite::connect(&url).unwrap();
+        join(&mut spectator, "club", Seat::Spectator, None);
+
+        let (status, body) = request(&address, "GET", "/games/club/moves/g1", "");
+        assert_eq!(status, 200);
+        assert_eq!(body["moves"].as_array().unwrap().len(), 2);
+
+        let (status, _) = request(&address, "POST", "/games/club/moves", r#"{"from": "e2", "to": "e5"}"#);
+        assert_eq!(status, 409);
+
+        let (status, body) = request(&address, "POST", "/games/club/moves", r#"{"from": "e2", "to": "e4"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(body["fen"], "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+        assert!(matches!(receive(&mut spectator), ServerMessage::Moved { .. }));
+
+        let (status, body) = request(&address, "POST", "/games/club/resign", r#"{"color": "black"}"#);
+        assert_eq!((status, body["state"].as_str()), (200, Some("game_over")));
+        assert!(matches!(receive(&mut spectator), ServerMessage::Resigned { .. }));
+
+        let (_, body) = request(&address, "GET", "/games/club/pgn", "");
+        assert!(body["pgn"].as_str().unwrap().ends_with("1. e4 1-0\n"));
+
+        let (status, _) = request(&address, "GET", "/games/missing", "");
+        assert_eq!(status, 404);
+    }
+
+    // once players are seated, HTTP calls only play for the seat of their token
+    #[test]
+    fn http_needs_seat_token() {
+        let (url, address) = start_servers();
+        request(&address, "POST", "/games", r#"{"game": "club"}"#);
+
+        let (mut white, _) = tungstenite::connect(&url).unwrap();
+        let (mut black, _) = tungstenite::connect(&url).unwrap();
+        let white_token = join(&mut white, "club", Seat::White, None).unwrap();
+        let black_token = join(&mut black, "club", Seat::Black, None).unwrap();
+
+        let (status, _) = request(&address, "POST", "/games/club/moves", r#"{"from": "e2", "to": "e4"}"#);
+        assert_eq!(status, 403);
+        let (status, _) = request(&address, "POST", "/games/club/moves", r#"{"from": "e2", "to": "e4", "token": "guess"}"#);
+        assert_eq!(status, 403);
+        let (status, _) = request(&address, "POST", "/games/club/resign", r#"{"color": "black"}"#);
+        assert_eq!(status, 403);
+        let (status, _) = request(&address, "POST", "/games/club/resign", &format!(r#"{{"color": "white", "token": "{}"}}"#, black_token));
+        assert_eq!(status, 403);
+
+        // the token of the player not to move is no help either
+        let (status, _) = request(&address, "POST", "/games/club/moves", &format!(r#"{{"from": "e2", "to": "e4", "token": "{}"}}"#, black_token));
+        assert_eq!(status, 409);
+        let (_, body) = request(&address, "GET", "/games/club", "");
+        assert_eq!(body["fen"], "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        let (status, _) = request(&address, "POST", "/games/club/moves", &format!(r#"{{"from": "e2", "to": "e4", "token": "{}"}}"#, white_token));
+        assert_eq!(status, 200);
+        assert!(matches!(receive(&mut black), ServerMessage::Moved { .. }));
+
+        let (status, body) = request(&address, "POST", "/games/club/resign", &format!(r#"{{"token": "{}"}}"#, black_token));
+        assert_eq!((status, body["state"].as_str()), (200, Some("game_over")));
+        assert_eq!(receive(&mut black), ServerMessage::Resigned { game: "club".to_string(), color: super::protocol::PlayerColor::Black });
+    }
+}
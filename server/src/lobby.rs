@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+
+use rasan_chess::{Color, Game, GameState};
+
+use crate::protocol::{Checks, ClientMessage, PromotionRole, Seat, ServerMessage};
+
+pub type ClientId = u64;
+
+/// All games hosted by the server and the clients connected to them.
+///
+/// The lobby knows nothing about sockets, every client is represented by
+/// the channel its connection reads outgoing messages from.
+pub struct Lobby {
+    rooms: HashMap<String, Room>,
+    clients: HashMap<ClientId, Client>,
+    next_client: ClientId,
+    next_game: u64
+}
+
+struct Client {
+    outbox: Sender<ServerMessage>,
+    game: Option<String>,
+    seat: Seat
+}
+
+struct Room {
+    game: Game,
+    white: Option<Player>,
+    black: Option<Player>,
+    spectators: Vec<ClientId>
+}
+
+// A seated player. The seat is kept when the connection drops
+struct Player {
+    client: Option<ClientId>,
+    token: String
+}
+
+impl Lobby {
+
+    pub fn new() -> Lobby {
+        return Lobby {
+            rooms: HashMap::new(),
+            clients: HashMap::new(),
+            next_client: 1,
+            next_game: 1
+        };
+    }
+
+    /// Register a new connection and return its id.
+    pub fn connect(&mut self, outbox: Sender<ServerMessage>) -> ClientId {
+        let id: ClientId = self.next_client;
+        self.next_client += 1;
+
+        self.clients.insert(id, Client {
+            outbox,
+            game: None,
+            seat: Seat::Spectator
+        });
+
+        return id;
+    }
+
+    /// Forget a connection. Players keep their seats so they can reconnect.
+    pub fn disconnect(&mut self, client: ClientId) {
+        self.leave(client);
+        self.clients.remove(&client);
+    }
+
+    /// Handle a message from a client, replying and broadcasting as needed.
+    pub fn handle(&mut self, client: ClientId, message: ClientMessage) {
+        let result: Result<(), String> = match message {
+            ClientMessage::Create { game } => self.create(client, game),
+            ClientMessage::Join { game, seat, token } => self.join(client, game, seat, token),
+            ClientMessage::Move { from, to } => self.make_move(client, from, to),
+            ClientMessage::Promote { square, role } => self.promote(client, square, role),
+            ClientMessage::Resign => self.resign(client),
+            ClientMessage::State => self.state(client)
+        };
+
+        // Report failures to the sender only
+        if let Err(message) = result {
+            self.send(client, ServerMessage::Error { message });
+        }
+    }
+
+    fn create(&mut self, client: ClientId, game: Option<String>) -> Result<(), String> {
+        let id: String = self.create_game(game)?;
+        self.send(client, ServerMessage::Created { game: id });
+        return Ok(());
+    }
+
+    fn join(&mut self, client: ClientId, game: String, seat: Seat, token: Option<String>) -> Result<(), String> {
+        let room: &Room = self.room(&game)?;
+
+        // Only the holder of the token may take an occupied seat, checked before the
+        // client gives up the seat it has
+        let occupant: Option<&Player> = match seat {
+            Seat::White => room.white.as_ref(),
+            Seat::Black => room.black.as_ref(),
+            Seat::Spectator => None
+        };
+        if let Some(player) = occupant {
+            if token.as_ref() != Some(&player.token) {
+                return Err(String::from("Seat is taken"));
+            }
+        }
+        let new_token: Option<String> = match (seat, occupant) {
+            (Seat::White, None) | (Seat::Black, None) => Some(new_token()?),
+            _ => None
+        };
+
+        // A connection only takes part in one game at a time
+        self.leave(client);
+
+        let room: &mut Room = self.rooms.get_mut(&game).unwrap();
+        let mut rejoined: bool = false;
+        let given_token: Option<String> = match seat {
+            Seat::Spectator => {
+                room.spectators.push(client);
+                None
+            },
+            Seat::White | Seat::Black => {
+                let player: &mut Option<Player> = match seat {
+                    Seat::White => &mut room.white,
+                    _ => &mut room.black
+                };
+
+                match player {
+                    Some(player) => {
+                        player.client = Some(client);
+                        rejoined = true;
+                        Some(player.token.clone())
+                    },
+                    None => {
+                        let token: String = new_token.unwrap();
+                        *player = Some(Player {
+                            client: Some(client),
+                            token: token.clone()
+                        });
+                        Some(token)
+                    }
+                }
+            }
+        };
+
+        // Remember where the client is
+        if let Some(entry) = self.clients.get_mut(&client) {
+            entry.game = Some(game.clone());
+            entry.seat = seat;
+        }
+
+        self.send(client, ServerMessage::Joined {
+            game: game.clone(),
+            seat,
+            token: given_token
+        });
+        self.state(client)?;
+
+        if rejoined {
+            self.broadcast(&game, ServerMessage::PlayerRejoined { game: game.clone(), seat });
+        }
+
+        return Ok(());
+    }
+
+    fn make_move(&mut self, client: ClientId, from: String, to: String) -> Result<(), String> {
+        let (game, color) = self.seated(client)?;
+        self.play(&game, Some(color), from, to)?;
+        return Ok(());
+    }
+
+    fn promote(&mut self, client: ClientId, square: String, role: PromotionRole) -> Result<(), String> {
+        let (game, color) = self.seated(client)?;
+        self.promote_pawn(&game, Some(color), square, role)?;
+        return Ok(());
+    }
+
+    fn resign(&mut self, client: ClientId) -> Result<(), String> {
+        let (game, color) = self.seated(client)?;
+        return self.resign_game(&game, color);
+    }
+
+    fn state(&mut self, client: ClientId) -> Result<(), String> {
+        let game: String = match self.clients.get(&client).and_then(|entry| entry.game.clone()) {
+            Some(game) => game,
+            None => return Err(String::from("Not in a game"))
+        };
+
+        let snapshot: ServerMessage = self.snapshot(&game)?;
+        self.send(client, snapshot);
+        return Ok(());
+    }
+
+    /// Create a game and return its id. The next free number is used if no id is given.
+    pub fn create_game(&mut self, game: Option<String>) -> Result<String, String> {
+        let id: String = match game {
+            Some(id) => id,
+            None => {
+                while self.rooms.contains_key(&self.next_game.to_string()) {
+                    self.next_game += 1;
+                }
+                self.next_game.to_string()
+            }
+        };
+
+        if self.rooms.contains_key(&id) {
+            return Err(format!("Game {} already exists", id));
+        }
+
+        self.rooms.insert(id.clone(), Room {
+            game: Game::new(),
+            white: None,
+            black: None,
+            spectators: Vec::new()
+        });
+
+        return Ok(id);
+    }
+
+    /// Get a hosted game.
+    pub fn game(&self, game: &str) -> Option<&Game> {
+        return self.rooms.get(game).map(|room| &room.game);
+    }
+
+    /// Snapshot of a game as sent to clients.
+    pub fn snapshot(&self, game: &str) -> Result<ServerMessage, String> {
+        let room: &Room = self.room(game)?;
+
+        return Ok(ServerMessage::State {
+            game: game.to_string(),
+            board: format!("{:?}", room.game),
+            fen: room.game.to_fen(),
+            active_color: room.game.get_active_color().into(),
+            state: room.game.get_game_state().into(),
+            checks: (&room.game).into()
+        });
+    }
+
+    /// Play a move and tell everyone in the game. Without a color the
+    /// move is played for whoever is to move.
+    pub fn play(&mut self, game: &str, color: Option<Color>, from: String, to: String) -> Result<GameState, String> {
+        let room: &mut Room = self.room_mut(game)?;
+
+        let mover: Color = room.game.get_active_color();
+        if color.is_some() && color != Some(mover.clone()) {
+            return Err(String::from("Not your turn"));
+        }
+
+        let state: GameState = match room.game.try_make_move(&from, &to) {
+            Ok(state) => state,
+            Err(error) => return Err(format!("{}: {} to {}", error, from, to))
+        };
+        let checks: Checks = (&room.game).into();
+
+        self.broadcast(game, ServerMessage::Moved {
+            game: game.to_string(),
+            color: mover.into(),
+            from,
+            to,
+            state: state.into(),
+            checks
+        });
+
+        return Ok(state);
+    }
+
+    /// Promote the pawn that just reached the last rank and tell everyone in the game.
+    pub fn promote_pawn(&mut self, game: &str, color: Option<Color>, square: String, role: PromotionRole) -> Result<GameState, String> {
+        let room: &mut Room = self.room_mut(game)?;
+
+        // The promoting player has already handed over the turn
+        if room.game.get_game_state() != GameState::Promotion || color == Some(room.game.get_active_color()) {
+            return Err(String::from("No promotion pending"));
+        }
+
+        let state: GameState = match room.game.try_set_promotion(&square, role.into()) {
+            Ok(state) => state,
+            Err(error) => return Err(format!("{}: {}", error, square))
+        };
+        let checks: Checks = (&room.game).into();
+
+        self.broadcast(game, ServerMessage::Promoted {
+            game: game.to_string(),
+            square,
+            role,
+            state: state.into(),
+            checks
+        });
+
+        return Ok(state);
+    }
+
+    /// Resign for a color and tell everyone in the game.
+    pub fn resign_game(&mut self, game: &str, color: Color) -> Result<(), String> {
+        let room: &mut Room = self.room_mut(game)?;
+
+        if room.game.get_game_state() == GameState::GameOver {
+            return Err(String::from("Game is over"));
+        }
+        room.game.resign(color.clone());
+
+        self.broadcast(game, ServerMessage::Resigned {
+            game: game.to_string(),
+            color: color.into()
+        });
+
+        return Ok(());
+    }
+
+    /// Color of the seat a token holds. Without a token only games nobody is
+    /// seated at may be played, for whoever is to move.
+    pub fn authorize(&self, game: &str, token: Option<&str>) -> Result<Option<Color>, String> {
+        let room: &Room = self.room(game)?;
+
+        let token: &str = match token {
+            Some(token) => token,
+            None if room.white.is_none() && room.black.is_none() => return Ok(None),
+            None => return Err(String::from("Seat token required"))
+        };
+        for (color, player) in [(Color::White, &room.white), (Color::Black, &room.black)].iter() {
+            if let Some(player) = player {
+                if player.token == token {
+                    return Ok(Some(color.clone()));
+                }
+            }
+        }
+        return Err(String::from("Token holds no seat"));
+    }
+
+    fn room(&self, game: &str) -> Result<&Room, String> {
+        match self.rooms.get(game) {
+            Some(room) => return Ok(room),
+            None => return Err(format!("No game with id {}", game))
+        }
+    }
+
+    fn room_mut(&mut self, game: &str) -> Result<&mut Room, String> {
+        match self.rooms.get_mut(game) {
+            Some(room) => return Ok(room),
+            None => return Err(format!("No game with id {}", game))
+        }
+    }
+
+    // Game id and color of a client sitting at a board
+    fn seated(&self, client: ClientId) -> Result<(String, Color), String> {
+        match self.clients.get(&client) {
+            Some(entry) => match (&entry.game, entry.seat.color()) {
+                (Some(game), Some(color)) => return Ok((game.clone(), color)),
+                (Some(_), None) => return Err(String::from("Spectators cannot play")),
+                _ => return Err(String::from("Not in a game"))
+            },
+            None => return Err(String::from("Unknown client"))
+        }
+    }
+
+    // Detach a client from its current game, if any
+    fn leave(&mut self, client: ClientId) {
+        let (game, seat) = match self.clients.get_mut(&client) {
+            Some(entry) => match entry.game.take() {
+                Some(game) => (game, entry.seat),
+                None => return
+            },
+            None => return
+        };
+
+        let room: &mut Room = match self.rooms.get_mut(&game) {
+            Some(room) => room,
+            None => return
+        };
+
+        match seat {
+            Seat::Spectator => room.spectators.retain(|id| *id != client),
+            Seat::White | Seat::Black => {
+                let player: &mut Option<Player> = match seat {
+                    Seat::White => &mut room.white,
+                    _ => &mut room.black
+                };
+
+                // Keep the seat, but only if no newer connection took it over
+                if let Some(player) = player {
+                    if player.client == Some(client) {
+                        player.client = None;
+                    } else {
+                        return;
+                    }
+                }
+                self.broadcast(&game, ServerMessage::PlayerLeft { game: game.clone(), seat });
+            }
+        }
+    }
+
+    // Send a message to everyone watching or playing a game
+    fn broadcast(&self, game: &str, message: ServerMessage) {
+        let room: &Room = match self.rooms.get(game) {
+            Some(room) => room,
+            None => return
+        };
+
+        let players = [&room.white, &room.black];
+        let seated = players.iter().filter_map(|player| player.as_ref().and_then(|player| player.client));
+        for client in seated.chain(room.spectators.iter().copied()) {
+            self.send(client, message.clone());
+        }
+    }
+
+    fn send(&self, client: ClientId, message: ServerMessage) {
+        if let Some(entry) = self.clients.get(&client) {
+
+            // The connection may already be closing, nothing to do then
+            let _ = entry.outbox.send(message);
+        }
+    }
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        return Lobby::new();
+    }
+}
+
+// Token handed out to players to reclaim their seat, 128 bits from the
+// random source of the operating system
+fn new_token() -> Result<String, String> {
+    let mut bytes: [u8; 16] = [0; 16];
+    if getrandom::getrandom(&mut bytes).is_err() {
+        return Err(String::from("Could not create a seat token"));
+    }
+    return Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect());
+}
@@ -0,0 +1,45 @@
+use std::env;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use rasan_chess_server::lobby::Lobby;
+
+// Addresses used when none are given on the command line
+const DEFAULT_ADDRESS: &str = "127.0.0.1:9001";
+const DEFAULT_HTTP_ADDRESS: &str = "127.0.0.1:8080";
+
+fn main() {
+    let address: String = env::args().nth(1).unwrap_or_else(|| String::from(DEFAULT_ADDRESS));
+    let http_address: String = env::args().nth(2).unwrap_or_else(|| String::from(DEFAULT_HTTP_ADDRESS));
+
+    // Both servers host the same games
+    let lobby: Arc<Mutex<Lobby>> = Arc::new(Mutex::new(Lobby::new()));
+
+    let listener: TcpListener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Could not listen on {}: {}", address, error);
+            std::process::exit(1);
+        }
+    };
+
+    let http_server: tiny_http::Server = match tiny_http::Server::http(&http_address) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("Could not listen on {}: {}", http_address, error);
+            std::process::exit(1);
+        }
+    };
+
+    println!("Chess royale server listening on ws://{}", address);
+    println!("HTTP API listening on http://{}", http_address);
+
+    let http_lobby: Arc<Mutex<Lobby>> = Arc::clone(&lobby);
+    thread::spawn(move || rasan_chess_server::http::serve(http_server, http_lobby));
+
+    if let Err(error) = rasan_chess_server::serve(listener, lobby) {
+        eprintln!("Server stopped: {}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use rasan_chess::{Color, Game, GameState, Role};
+
+/* JSON message schema shared by the server and its clients.
+ *
+ * Every message is an object with a "type" field naming the message,
+ * e.g. {"type": "move", "from": "e2", "to": "e4"}.
+ */
+
+/// Messages sent from a client to the server.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Create a new game. The server picks an id if none is given.
+    Create {
+        #[serde(default)]
+        game: Option<String>
+    },
+
+    /// Join a game as a player or spectator. Passing the token received
+    /// when first joining reclaims the seat after a reconnect.
+    Join {
+        game: String,
+        seat: Seat,
+        #[serde(default)]
+        token: Option<String>
+    },
+
+    /// Move a piece in the joined game.
+    Move { from: String, to: String },
+
+    /// Choose the piece a pawn is promoted to.
+    Promote { square: String, role: PromotionRole },
+
+    /// Give up the joined game.
+    Resign,
+
+    /// Ask for a snapshot of the joined game.
+    State
+}
+
+/// Messages sent from the server to a client.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// A game has been created.
+    Created { game: String },
+
+    /// The client has joined a game. Players receive a token for reconnects.
+    Joined {
+        game: String,
+        seat: Seat,
+        token: Option<String>
+    },
+
+    /// Full snapshot of a game.
+    State {
+        game: String,
+        board: String,
+        fen: String,
+        active_color: PlayerColor,
+        state: Status,
+        checks: Checks
+    },
+
+    /// A move has been played.
+    Moved {
+        game: String,
+        color: PlayerColor,
+        from: String,
+        to: String,
+        state: Status,
+        checks: Checks
+    },
+
+    /// A pawn has been promoted.
+    Promoted {
+        game: String,
+        square: String,
+        role: PromotionRole,
+        state: Status,
+        checks: Checks
+    },
+
+    /// A player has resigned.
+    Resigned { game: String, color: PlayerColor },
+
+    /// A player lost the connection. The seat is kept for a reconnect.
+    PlayerLeft { game: String, seat: Seat },
+
+    /// A player has reclaimed the seat.
+    PlayerRejoined { game: String, seat: Seat },
+
+    /// The last request could not be handled.
+    Error { message: String }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Seat {
+    White,
+    Black,
+    Spectator
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerColor {
+    White,
+    Black
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PromotionRole {
+    Queen,
+    Rook,
+    Bishop,
+    Knight
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    InProgress,
+    Promotion,
+    Check,
+    GameOver,
+    DuckPlacement
+}
+
+/// How many times each color has given check, counted in Three-check.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+pub struct Checks {
+    pub white: u32,
+    pub black: u32
+}
+
+impl From<&Game> for Checks {
+    fn from(game: &Game) -> Self {
+        return Checks { white: game.get_checks(&Color::White), black: game.get_checks(&Color::Black) };
+    }
+}
+
+impl From<Color> for PlayerColor {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::White => PlayerColor::White,
+            Color::Black => PlayerColor::Black
+        }
+    }
+}
+
+impl From<PlayerColor> for Color {
+    fn from(color: PlayerColor) -> Self {
+        match color {
+            PlayerColor::White => Color::White,
+            PlayerColor::Black => Color::Black
+        }
+    }
+}
+
+impl From<PromotionRole> for Role {
+    fn from(role: PromotionRole) -> Self {
+        match role {
+            PromotionRole::Queen => Role::Queen,
+            PromotionRole::Rook => Role::Rook,
+            PromotionRole::Bishop => Role::Bishop,
+            PromotionRole::Knight => Role::Knight
+        }
+    }
+}
+
+impl From<GameState> for Status {
+    fn from(state: GameState) -> Self {
+        match state {
+            GameState::InProgress => Status::InProgress,
+            GameState::Promotion => Status::Promotion,
+            GameState::Check => Status::Check,
+            GameState::GameOver => Status::GameOver,
+            GameState::DuckPlacement => Status::DuckPlacement
+        }
+    }
+}
+
+impl Seat {
+    // The color a seat plays, spectators have none
+    pub fn color(self) -> Option<Color> {
+        match self {
+            Seat::White => Some(Color::White),
+            Seat::Black => Some(Color::Black),
+            Seat::Spectator => None
+        }
+    }
+}